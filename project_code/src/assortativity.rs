@@ -0,0 +1,124 @@
+//! Module for attribute assortativity and homophily, measuring whether streamers befriend streamers like themselves
+use std::collections::{HashMap, HashSet};
+use crate::data_analysis::pearson_correlation;
+
+/// Lists every undirected edge in both directions, once as (a, b) and once as (b, a) \
+/// Nodes are visited in sorted order so sums over the edges are always taken in the same order \
+/// Edges with an endpoint missing from has_value are skipped
+fn directed_edges<'a>(graph: &'a HashMap<u32, HashSet<u32>>, has_value: impl Fn(&u32) -> bool + 'a) -> Vec<(u32, u32)> {
+    let mut nodes: Vec<&u32> = graph.keys().filter(|node| has_value(node)).collect();
+    nodes.sort();
+    let mut edges = Vec::new();
+    for node in nodes {
+        let mut neighbors: Vec<&u32> = graph[node].iter().filter(|neighbor| has_value(neighbor)).collect();
+        neighbors.sort();
+        edges.extend(neighbors.into_iter().map(|neighbor| (*node, *neighbor)));
+    }
+    edges
+}
+
+/// Builds the mixing matrix of a categorical attribute \
+/// **Input:** HashMap graph, HashMap of node ids to categories 0..category_count, and category_count \
+/// Each undirected edge adds 1 to cell \[a\]\[b\] and 1 to cell \[b\]\[a\], so the matrix is symmetric \
+/// **Output:** category_count x category_count matrix of edge end counts
+pub fn mixing_matrix(graph: &HashMap<u32, HashSet<u32>>, categories: &HashMap<u32, usize>, category_count: usize) -> Vec<Vec<u64>> {
+    let mut matrix = vec![vec![0u64; category_count]; category_count];
+    for (a, b) in directed_edges(graph, |node| categories.contains_key(node)) {
+        matrix[categories[&a]][categories[&b]] += 1;
+    }
+    matrix
+}
+
+/// Finds Newman's assortativity coefficient for a categorical attribute from its mixing matrix \
+/// r = (sum of e_ii - sum of a_i * b_i) / (1 - sum of a_i * b_i), where e is the matrix as fractions of all edge ends
+/// and a_i, b_i are its row and column sums \
+/// **Output:** 1.0 when edges only join matching categories, 0.0 for random mixing, negative when unlike categories link
+pub fn categorical_assortativity(matrix: &[Vec<u64>]) -> f64 {
+    let total: u64 = matrix.iter().flatten().sum();
+    if total == 0 {
+        return 0.0
+    }
+    let total = total as f64;
+    let trace: f64 = (0..matrix.len()).map(|idx| matrix[idx][idx] as f64 / total).sum();
+    let expected: f64 = (0..matrix.len())
+        .map(|idx| {
+            let row_sum: u64 = matrix[idx].iter().sum();
+            let col_sum: u64 = matrix.iter().map(|row| row[idx]).sum();
+            (row_sum as f64 / total) * (col_sum as f64 / total)
+        })
+        .sum();
+    if expected == 1.0 { // Every node shares one category, so there is no mixing to measure
+        return 0.0
+    }
+    (trace - expected) / (1.0 - expected)
+}
+
+/// Finds the edge homophily ratio, the share of edges joining two nodes of the same category \
+/// **Output:** (observed ratio, ratio expected if edges ignored the attribute), from the same mixing matrix
+pub fn edge_homophily(matrix: &[Vec<u64>]) -> (f64, f64) {
+    let total: u64 = matrix.iter().flatten().sum();
+    if total == 0 {
+        return (0.0, 0.0)
+    }
+    let total = total as f64;
+    let observed = (0..matrix.len()).map(|idx| matrix[idx][idx] as f64).sum::<f64>() / total;
+    let expected = matrix.iter().map(|row| (row.iter().sum::<u64>() as f64 / total).powi(2)).sum();
+    (observed, expected)
+}
+
+/// Finds the assortativity coefficient of a numeric attribute \
+/// **Input:** HashMap graph and HashMap of node ids to values, such as views, days or degree \
+/// **Output:** Pearson correlation between the values at either end of every edge,
+/// with each edge counted in both directions so the result is symmetric, or None if no edge has values at both ends
+pub fn numeric_assortativity(graph: &HashMap<u32, HashSet<u32>>, values: &HashMap<u32, f64>) -> Option<f64> {
+    let edges = directed_edges(graph, |node| values.contains_key(node));
+    let start_values: Vec<f64> = edges.iter().map(|(a, _)| values[a]).collect();
+    let end_values: Vec<f64> = edges.iter().map(|(_, b)| values[b]).collect();
+    pearson_correlation(&start_values, &end_values)
+}
+
+/// Bins numeric values into quantile categories, such as quartiles when bins is 4 \
+/// Ties are kept in the same bin, so bins may be uneven for heavily tied values \
+/// **Output:** HashMap of node ids to bins 0..bins, for use with mixing_matrix
+pub fn quantile_bins(values: &HashMap<u32, f64>, bins: usize) -> HashMap<u32, usize> {
+    let mut sorted: Vec<f64> = values.values().cloned().collect();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    values.iter()
+        .map(|(node, value)| {
+            let below = sorted.partition_point(|other| other < value); // Position of the first tied value
+            (*node, (below * bins / sorted.len()).min(bins - 1))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+use super::*;
+    use crate::random_graphs::graph_from_edges;
+
+    /// Perfectly sorted and perfectly mixed graphs should give +1 and -1
+    #[test]
+    fn test_categorical_assortativity() {
+        let categories = HashMap::from([(1, 0), (2, 0), (3, 1), (4, 1)]);
+
+        let sorted = mixing_matrix(&graph_from_edges(&[(1, 2), (3, 4)]), &categories, 2);
+        assert_eq!(sorted, vec![vec![2, 0], vec![0, 2]]);
+        assert_eq!(categorical_assortativity(&sorted), 1.0);
+        assert_eq!(edge_homophily(&sorted), (1.0, 0.5));
+
+        let mixed = mixing_matrix(&graph_from_edges(&[(1, 3), (2, 4)]), &categories, 2);
+        assert_eq!(categorical_assortativity(&mixed), -1.0);
+        assert_eq!(edge_homophily(&mixed).0, 0.0);
+    }
+
+    /// A star graph is perfectly disassortative by degree, and quartile bins split four values evenly
+    #[test]
+    fn test_numeric_assortativity() {
+        let star = graph_from_edges(&[(0, 1), (0, 2), (0, 3)]);
+        let degrees: HashMap<u32, f64> = star.iter().map(|(node, neighbors)| (*node, neighbors.len() as f64)).collect();
+        assert!((numeric_assortativity(&star, &degrees).unwrap() + 1.0).abs() < 1e-9);
+
+        let values = HashMap::from([(1, 10.0), (2, 40.0), (3, 20.0), (4, 30.0)]);
+        assert_eq!(quantile_bins(&values, 4), HashMap::from([(1, 0), (3, 1), (4, 2), (2, 3)]));
+    }
+}
@@ -0,0 +1,165 @@
+//! Module for network autocorrelation of node values, such as whether a streamer's friends' views predict their own
+use std::collections::{HashMap, HashSet};
+use plotters::prelude::*;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+
+/// Moran's I and Geary's C of one set of node values, with permutation p-values
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AutocorrelationResult {
+    pub morans_i: f64,
+    pub expected_i: f64,
+    pub morans_p: f64,
+    pub gearys_c: f64,
+    pub gearys_p: f64,
+}
+
+/// Finds Moran's I and Geary's C over the graph with binary (adjacency) weights \
+/// ### Inputs
+/// graph: HashMap graph such as the output of csv_to_hashmap \
+/// values: HashMap of node ids to values, such as views, nodes missing a value are left out of the graph \
+/// permutations: number of random shuffles of the values used for the p-values, such as 999 \
+/// seed: seed for the shuffles \
+/// ### Formulas
+/// Moran's I = (N / W) * sum over edges of (x_i - mean)(x_j - mean) / sum of (x_i - mean)^2 \
+/// Geary's C = (N - 1) * sum over edges of (x_i - x_j)^2 / (2W * sum of (x_i - mean)^2) \
+/// where W counts each undirected edge twice. I above -1 / (N - 1) and C below 1 mean neighbors have similar values \
+/// ### Significance
+/// The values are shuffled across the nodes, keeping the graph fixed, and both statistics recomputed \
+/// Each p-value is the two-sided share of shuffles at least as far from the expected value (-1 / (N - 1) for I, 1 for C)
+/// as the observed statistic, counting the observed statistic as one of the shuffles
+pub fn autocorrelation(graph: &HashMap<u32, HashSet<u32>>, values: &HashMap<u32, f64>, permutations: usize, seed: u64) -> AutocorrelationResult {
+    let mut nodes: Vec<u32> = graph.keys().filter(|node| values.contains_key(node)).cloned().collect();
+    nodes.sort();
+    let index: HashMap<u32, usize> = nodes.iter().enumerate().map(|(idx, node)| (*node, idx)).collect();
+    let mut edges: Vec<(usize, usize)> = Vec::new(); // Every undirected edge in both directions
+    for (idx, node) in nodes.iter().enumerate() {
+        let mut neighbors: Vec<usize> = graph[node].iter().filter_map(|neighbor| index.get(neighbor).cloned()).collect();
+        neighbors.sort();
+        edges.extend(neighbors.into_iter().map(|neighbor| (idx, neighbor)));
+    }
+    let mut node_values: Vec<f64> = nodes.iter().map(|node| values[node]).collect();
+
+    let n = nodes.len() as f64;
+    let expected_i = if n > 1.0 { -1.0 / (n - 1.0) } else { 0.0 };
+    let (morans_i, gearys_c) = morans_and_gearys(&edges, &node_values);
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut extreme_i = 1; // The observed statistic counts as one of the shuffles
+    let mut extreme_c = 1;
+    for _ in 0..permutations {
+        node_values.shuffle(&mut rng);
+        let (shuffled_i, shuffled_c) = morans_and_gearys(&edges, &node_values);
+        if (shuffled_i - expected_i).abs() >= (morans_i - expected_i).abs() {
+            extreme_i += 1;
+        }
+        if (shuffled_c - 1.0).abs() >= (gearys_c - 1.0).abs() {
+            extreme_c += 1;
+        }
+    }
+
+    AutocorrelationResult {
+        morans_i,
+        expected_i,
+        morans_p: extreme_i as f64 / (permutations + 1) as f64,
+        gearys_c,
+        gearys_p: extreme_c as f64 / (permutations + 1) as f64,
+    }
+}
+
+/// Computes Moran's I and Geary's C for values indexed the same way as the directed edge list
+fn morans_and_gearys(edges: &[(usize, usize)], values: &[f64]) -> (f64, f64) {
+    let n = values.len() as f64;
+    let weight_sum = edges.len() as f64;
+    if n < 2.0 || weight_sum == 0.0 {
+        return (0.0, 1.0)
+    }
+    let mean = values.iter().sum::<f64>() / n;
+    let squared_deviations: f64 = values.iter().map(|value| (value - mean).powi(2)).sum();
+    if squared_deviations == 0.0 { // Every value is the same, so there is nothing to correlate
+        return (0.0, 1.0)
+    }
+    let mut cross_products = 0.0;
+    let mut squared_differences = 0.0;
+    for (a, b) in edges {
+        cross_products += (values[*a] - mean) * (values[*b] - mean);
+        squared_differences += (values[*a] - values[*b]).powi(2);
+    }
+    let morans_i = (n / weight_sum) * cross_products / squared_deviations;
+    let gearys_c = (n - 1.0) * squared_differences / (2.0 * weight_sum * squared_deviations);
+    (morans_i, gearys_c)
+}
+
+/// Finds the average value of each node's neighbors \
+/// **Output:** HashMap of node ids to (own value, average neighbor value), leaving out nodes with no valued neighbors
+pub fn neighbor_averages(graph: &HashMap<u32, HashSet<u32>>, values: &HashMap<u32, f64>) -> HashMap<u32, (f64, f64)> {
+    let mut averages = HashMap::new();
+    for (node, neighbors) in graph {
+        let Some(own_value) = values.get(node) else { continue };
+        let mut neighbor_values: Vec<f64> = neighbors.iter().filter_map(|neighbor| values.get(neighbor).cloned()).collect();
+        if neighbor_values.is_empty() {
+            continue
+        }
+        neighbor_values.sort_by(|a, b| a.total_cmp(b));
+        averages.insert(*node, (*own_value, neighbor_values.iter().sum::<f64>() / neighbor_values.len() as f64));
+    }
+    averages
+}
+
+/// Uses plotters to draw a scatter chart of each streamer's views against their friends' average views \
+/// **Input:** output of neighbor_averages on raw views, and the output filename \
+/// Both axes use a log scale of views + 1, since views span several orders of magnitude \
+/// **Output:** scatter chart written to the given .png file in the project_code folder
+pub fn plot_neighbor_views(averages: &HashMap<u32, (f64, f64)>, filename: &str) {
+    let max_views = averages.values().map(|(own, neighbor)| own.max(*neighbor)).fold(10.0, f64::max) * 2.0;
+
+    let root_area = BitMapBackend::new(filename, (1024, 768))
+        .into_drawing_area();
+    root_area.fill(&WHITE).unwrap();
+
+    let mut chart = ChartBuilder::on(&root_area)
+        .caption("Own Views vs Friends' Average Views", ("Arial", 20).into_font())
+        .x_label_area_size(40)
+        .y_label_area_size(60)
+        .margin(10)
+        .build_cartesian_2d((1f64..max_views).log_scale(), (1f64..max_views).log_scale()).unwrap();
+
+    chart.configure_mesh()
+        .x_desc("Own Views + 1")
+        .y_desc("Friends' Average Views + 1")
+        .x_label_formatter(&|x| format!("{}", *x as u64)) // Drop the decimals from the log scale labels
+        .y_label_formatter(&|y| format!("{}", *y as u64))
+        .draw()
+        .unwrap();
+
+    chart.draw_series(averages.values().map(|(own, neighbor)| { // Shift views by 1 so 0 fits on the log scale
+        Circle::new((own + 1.0, neighbor + 1.0), 2, BLUE.mix(0.3).filled())
+    }))
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+use super::*;
+
+    /// A path with smoothly rising values is positively autocorrelated, alternating values are negatively autocorrelated
+    #[test]
+    fn test_autocorrelation() {
+        let edges: Vec<(u32, u32)> = (0..20).map(|node| (node, node + 1)).collect();
+        let path = crate::random_graphs::graph_from_edges(&edges);
+
+        let rising: HashMap<u32, f64> = (0..21).map(|node| (node, node as f64)).collect();
+        let smooth = autocorrelation(&path, &rising, 199, 1);
+        assert!(smooth.morans_i > 0.8 && smooth.gearys_c < 0.2);
+        assert!(smooth.morans_p < 0.05 && smooth.gearys_p < 0.05, "Smooth values should be significant");
+
+        let alternating: HashMap<u32, f64> = (0..21).map(|node| (node, (node % 2) as f64)).collect();
+        let rough = autocorrelation(&path, &alternating, 199, 1);
+        assert!(rough.morans_i < -0.8 && rough.gearys_c > 1.5);
+
+        let averages = neighbor_averages(&path, &rising);
+        assert_eq!(averages[&0], (0.0, 1.0));
+        assert_eq!(averages[&5], (5.0, 5.0));
+    }
+}
@@ -1,13 +1,109 @@
 //! Module containing my implementation of the Bron-Kerbosch algorithm and associated tests, which references the copied_alg module
 
 use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::ops::ControlFlow;
+use std::time::{Duration, Instant};
+use crate::progress::ProgressEvent;
+
+/// Limits on a clique search, where None means no limit \
+/// The default budget is unlimited
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct SearchBudget {
+    pub time_limit: Option<Duration>, // Wall-clock time since the search started
+    pub max_calls: Option<u64>, // Recursive calls of the Bron-Kerbosch algorithm
+    pub max_cliques: Option<usize>, // Cliques of at least min_value saved
+}
+
+/// The SearchBudget limit that stopped a search early
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BudgetLimit {
+    Time,
+    Calls,
+    Cliques,
+}
+
+/// Recursive calls between reads of the clock, since reading it on every call slows the search down
+const CLOCK_INTERVAL: u64 = 4096;
+
+/// Tracks a search against its SearchBudget, stopping it at the first limit reached
+#[derive(Debug, Clone)]
+pub(crate) struct BudgetTracker {
+    budget: SearchBudget,
+    start: Instant,
+    calls: u64,
+    cliques: usize,
+    stopped_by: Option<BudgetLimit>,
+}
+
+impl BudgetTracker {
+    /// Starts tracking, with the clock starting now
+    pub fn new(budget: SearchBudget) -> Self {
+        BudgetTracker { budget, start: Instant::now(), calls: 0, cliques: 0, stopped_by: None }
+    }
+
+    /// Counts one recursive call, returning false if the search should stop before making it \
+    /// A search with max_calls makes exactly that many calls, and the time limit is checked every CLOCK_INTERVAL calls
+    fn charge_call(&mut self) -> bool {
+        if self.budget.max_calls.is_some_and(|max_calls| self.calls >= max_calls) {
+            self.stopped_by = Some(BudgetLimit::Calls);
+        } else if self.calls.is_multiple_of(CLOCK_INTERVAL) && self.budget.time_limit.is_some_and(|time_limit| self.start.elapsed() >= time_limit) {
+            self.stopped_by = Some(BudgetLimit::Time);
+        }
+        if self.stopped_by.is_none() {
+            self.calls += 1;
+        }
+        self.stopped_by.is_none()
+    }
+
+    /// Counts one saved clique, stopping the search once max_cliques have been saved
+    fn charge_clique(&mut self) {
+        self.cliques += 1;
+        if self.budget.max_cliques.is_some_and(|max_cliques| self.cliques >= max_cliques) {
+            self.stopped_by = Some(BudgetLimit::Cliques);
+        }
+    }
+
+    /// The limit that stopped the search, or None if it is still within budget
+    pub fn stopped_by(&self) -> Option<BudgetLimit> {
+        self.stopped_by
+    }
+
+    /// Recursive calls made so far
+    pub fn calls(&self) -> u64 {
+        self.calls
+    }
+
+    /// Time since tracking started
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// Cliques from maximal_cliques_within, which are every maximal clique of the graph only when stopped_by is None
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartialCliques<N> {
+    pub cliques: Vec<Vec<N>>, // Sorted, and each clique is maximal even when the search stopped early
+    pub stopped_by: Option<BudgetLimit>,
+    pub calls: u64, // Recursive calls made by this run, not counting earlier runs resumed from a checkpoint
+    pub elapsed: Duration, // Time spent on the search, including earlier runs resumed from a checkpoint
+}
+
+impl<N> PartialCliques<N> {
+    /// True when the search finished within budget, so no cliques are missing
+    pub fn is_complete(&self) -> bool {
+        self.stopped_by.is_none()
+    }
+}
+
 /// Runs the ['Bron-Kerbosch Algorithm'] \
 /// ### Inputs
 /// r: HashSet of nodes in the clique currently being built (initially empty) \
 /// p: HashSet of possible nodes left to explore (initially all nodes) \
 /// x: HashSet of excluded nodes, that have already been processed (initially empty) \
-/// graph: HashMap of u32 keys and HashSet<u32> values, where the HashSet represents edges from the key to other nodes \
-/// cliques: Vector of u32 vectors, initially empty. This is the output with all identified cliques \
+/// graph: HashMap of node keys and HashSet values, where the HashSet represents edges from the key to other nodes \
+/// Nodes can be any id type N, such as u32 ids from csv_to_hashmap, u64 ids or String usernames \
+/// cliques: Vector of node vectors, initially empty. This is the output with all identified cliques \
 /// min_value: a u32 value that sets the threshold for how many nodes must be in a clique to be saved in the cliques vector (inclusive) \
 /// ### Algorithm Logic
 /// If there are no unprocessed nodes (p is empty) and no processed nodes (x is empty) left { \
@@ -27,46 +123,190 @@ use std::collections::{HashMap, HashSet};
 /// Cliques is a vector of vectors, containing each of the cliques that passes the length threshold
 /// 
 /// ['Bron-Kerbosch Algorithm']: https://rosettacode.org/wiki/Bron%E2%80%93Kerbosch_algorithm
-pub fn run_bron_kerbosch(
-    r: &HashSet<u32>,
-    p: &mut HashSet<u32>,
-    x: &mut HashSet<u32>,
-    graph: &HashMap<u32, HashSet<u32>>,
-    cliques: &mut Vec<Vec<u32>>,
+pub fn run_bron_kerbosch<N: Clone + Eq + Hash + Ord>(
+    r: &HashSet<N>,
+    p: &mut HashSet<N>,
+    x: &mut HashSet<N>,
+    graph: &HashMap<N, HashSet<N>>,
+    cliques: &mut Vec<Vec<N>>,
     min_value: u32
 ) {
+    run_bron_kerbosch_within(r, p, x, graph, cliques, min_value, &mut BudgetTracker::new(SearchBudget::default()));
+}
+
+/// Runs the Bron-Kerbosch algorithm like run_bron_kerbosch, charging every recursive call and saved clique to tracker \
+/// Once a limit is reached every level returns straight away, leaving the cliques saved so far in cliques, each of them maximal \
+/// Check tracker.stopped_by() afterwards to see whether the search finished
+pub(crate) fn run_bron_kerbosch_within<N: Clone + Eq + Hash + Ord>(
+    r: &HashSet<N>,
+    p: &mut HashSet<N>,
+    x: &mut HashSet<N>,
+    graph: &HashMap<N, HashSet<N>>,
+    cliques: &mut Vec<Vec<N>>,
+    min_value: u32,
+    tracker: &mut BudgetTracker,
+) {
+    if !tracker.charge_call() {
+        return;
+    }
     if p.is_empty() && x.is_empty() {
         if r.len() >= min_value as usize { //Only save cliques that pass set threshold to reduce output to relevant options
-            let mut clique: Vec<u32> = r.clone().into_iter().collect();
+            let mut clique: Vec<N> = r.clone().into_iter().collect();
             clique.sort();
             cliques.push(clique);
+            tracker.charge_clique();
         } 
         return;
     }
     
+    for node in pivot_candidates(p, x, graph) {
+        let mut new_r = r.clone();
+        new_r.insert(node.clone()); // r now includes candidate node
+
+        let neighbors_of_node = graph.get(&node).cloned().unwrap_or_default(); 
+        let mut new_p = p.intersection(&neighbors_of_node).cloned().collect::<HashSet<N>>(); // New p equals all unprocessed neighbors
+
+        let mut new_x = x.intersection(&neighbors_of_node).cloned().collect::<HashSet<N>>(); // New x equals all processed neighbors
+
+        run_bron_kerbosch_within(&new_r, &mut new_p, &mut new_x, graph, cliques, min_value, tracker); // Recursively call until p and x are empty (see start of algorithm)
+        if tracker.stopped_by().is_some() {
+            return; // Out of budget, keep the cliques found so far
+        }
+        p.remove(&node); // the candidate node has now been processed and should move from p to x
+        x.insert(node);
+        }
+    }
+
+/// Finds every maximal clique of at least min_value nodes in the whole graph \
+/// Sets up r, p and x for run_bron_kerbosch, then sorts the cliques for consistent output
+pub fn maximal_cliques<N: Clone + Eq + Hash + Ord>(graph: &HashMap<N, HashSet<N>>, min_value: u32) -> Vec<Vec<N>> {
+    maximal_cliques_within(graph, min_value, SearchBudget::default()).cliques
+}
+
+/// Chooses the remaining node with the most neighbors as the pivot, returning the nodes of p to branch on \
+/// p and x must not both be empty
+fn pivot_candidates<N: Clone + Eq + Hash>(p: &HashSet<N>, x: &HashSet<N>, graph: &HashMap<N, HashSet<N>>) -> Vec<N> {
     let pivot = p // Choose pivot based on remaining node with the most neighbors
         .union(x)
         .max_by_key(|possible_node| graph.get(*possible_node).map(|neighbors| neighbors.len())) // Counts each node's neighbors, selects the max
         .unwrap();
 
-    
-    let neighbors = graph.get(&pivot).cloned().unwrap_or_default();
-    let candidates: Vec<u32> = p.difference(&neighbors).cloned().collect(); // Candidates are remaining unprocessed nodes that aren't neighbors to pivot (if we add a neighbor, we would also add pivot node)
+    let neighbors = graph.get(pivot).cloned().unwrap_or_default();
+    p.difference(&neighbors).cloned().collect() // Candidates are remaining unprocessed nodes that aren't neighbors to pivot (if we add a neighbor, we would also add pivot node)
+}
 
-    for node in candidates {
-        let mut new_r = r.clone();
-        new_r.insert(node.clone()); // r now includes candidate node
+/// Finds maximal cliques of at least min_value nodes like maximal_cliques, stopping early once any limit in budget is reached \
+/// **Output:** PartialCliques with the sorted cliques found, and the limit that stopped the search if it didn't finish
+pub fn maximal_cliques_within<N: Clone + Eq + Hash + Ord>(graph: &HashMap<N, HashSet<N>>, min_value: u32, budget: SearchBudget) -> PartialCliques<N> {
+    maximal_cliques_with_progress(graph, min_value, budget, &mut |_| ())
+}
 
-        let neighbors_of_node = graph.get(&node).cloned().unwrap_or_default(); 
-        let mut new_p = p.intersection(&neighbors_of_node).cloned().collect::<HashSet<u32>>(); // New p equals all unprocessed neighbors
+/// Finds maximal cliques like maximal_cliques_within, calling on_progress after each top-level vertex of the search \
+/// Each ProgressEvent::Cliques counts how many top-level vertices are done, see SearchState, such as for a progress::ProgressBar \
+/// **Output:** PartialCliques with the sorted cliques found, and the limit that stopped the search if it didn't finish
+pub fn maximal_cliques_with_progress<N: Clone + Eq + Hash + Ord>(
+    graph: &HashMap<N, HashSet<N>>,
+    min_value: u32,
+    budget: SearchBudget,
+    on_progress: &mut dyn FnMut(&ProgressEvent),
+) -> PartialCliques<N> {
+    let mut tracker = BudgetTracker::new(budget);
+    let mut state = SearchState::new(graph);
+    let unfinished = continue_search(graph, min_value, &mut state, &mut tracker, &mut |state, tracker| {
+        on_progress(&state.progress(tracker));
+        ControlFlow::Continue(())
+    });
+    state.finish(unfinished, &tracker)
+}
 
-        let mut new_x = x.intersection(&neighbors_of_node).cloned().collect::<HashSet<u32>>(); // New x equals all processed neighbors
+/// How far the top level of a search has got, so it can be stopped and continued later, such as from a checkpoint file \
+/// The top level branches on every node that isn't a neighbor of the highest degree node, in sorted order
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchState<N> {
+    pub candidates: Vec<N>, // Top-level vertices, in the order they are searched
+    pub processed: usize, // Top-level vertices finished
+    pub cliques: Vec<Vec<N>>, // Cliques found by the finished vertices
+    pub elapsed_before: Duration, // Time spent on earlier runs of this search, before it was continued
+}
 
-        run_bron_kerbosch(&new_r, &mut new_p, &mut new_x, graph, cliques, min_value); // Recursively call until p and x are empty (see start of algorithm)
-        p.remove(&node); // the candidate node has now been processed and should move from p to x
+impl<N: Clone + Eq + Hash + Ord> SearchState<N> {
+    /// State of a search that hasn't started
+    pub fn new(graph: &HashMap<N, HashSet<N>>) -> Self {
+        let mut candidates = if graph.is_empty() {
+            Vec::new()
+        } else {
+            pivot_candidates(&graph.keys().cloned().collect(), &HashSet::new(), graph)
+        };
+        candidates.sort(); // Sorted so the search runs through the vertices in the same order every time
+        SearchState { candidates, processed: 0, cliques: Vec::new(), elapsed_before: Duration::ZERO }
+    }
+
+    /// True once every top-level vertex is finished
+    pub fn is_finished(&self) -> bool {
+        self.processed == self.candidates.len()
+    }
+
+    /// Time spent on this search over every run, with tracker timing the current run
+    pub(crate) fn elapsed(&self, tracker: &BudgetTracker) -> Duration {
+        self.elapsed_before + tracker.elapsed()
+    }
+
+    /// ProgressEvent::Cliques for the state so far
+    pub(crate) fn progress(&self, tracker: &BudgetTracker) -> ProgressEvent {
+        ProgressEvent::Cliques { processed: self.processed, total: self.candidates.len(), cliques: self.cliques.len(), elapsed: self.elapsed(tracker) }
+    }
+
+    /// Ends the search, adding the cliques continue_search returned from an unfinished vertex \
+    /// **Output:** PartialCliques with every clique found sorted, stopped by the tracker's limit if any
+    pub(crate) fn finish(self, unfinished: Vec<Vec<N>>, tracker: &BudgetTracker) -> PartialCliques<N> {
+        let elapsed = self.elapsed(tracker);
+        let mut cliques = self.cliques;
+        cliques.extend(unfinished);
+        cliques.sort();
+        PartialCliques { cliques, stopped_by: tracker.stopped_by(), calls: tracker.calls(), elapsed }
+    }
+}
+
+/// Continues a search from state until every top-level vertex is finished or tracker runs out of budget \
+/// after_vertex is called each time a vertex is finished and added to state, such as to report progress or write a checkpoint,
+/// and can return ControlFlow::Break to stop the search there \
+/// **Output:** cliques found by a vertex the budget cut short, which are left out of state so it only ever holds finished vertices
+pub(crate) fn continue_search<N: Clone + Eq + Hash + Ord>(
+    graph: &HashMap<N, HashSet<N>>,
+    min_value: u32,
+    state: &mut SearchState<N>,
+    tracker: &mut BudgetTracker,
+    after_vertex: &mut dyn FnMut(&SearchState<N>, &BudgetTracker) -> ControlFlow<()>,
+) -> Vec<Vec<N>> {
+    if graph.is_empty() {
+        run_bron_kerbosch_within(&HashSet::new(), &mut HashSet::new(), &mut HashSet::new(), graph, &mut state.cliques, min_value, tracker); // Saves the empty clique if min_value is 0
+        return Vec::new()
+    }
+    if !tracker.charge_call() { // The top level counts as one call, as in run_bron_kerbosch_within
+        return Vec::new()
+    }
+    // Finished vertices have moved from p to x, exactly as in run_bron_kerbosch_within
+    let mut x: HashSet<N> = state.candidates[..state.processed].iter().cloned().collect();
+    let mut p: HashSet<N> = graph.keys().filter(|node| !x.contains(*node)).cloned().collect();
+    while !state.is_finished() {
+        let node = state.candidates[state.processed].clone();
+        let neighbors_of_node = graph.get(&node).cloned().unwrap_or_default();
+        let mut new_p = p.intersection(&neighbors_of_node).cloned().collect::<HashSet<N>>();
+        let mut new_x = x.intersection(&neighbors_of_node).cloned().collect::<HashSet<N>>();
+        let before = state.cliques.len();
+        run_bron_kerbosch_within(&HashSet::from([node.clone()]), &mut new_p, &mut new_x, graph, &mut state.cliques, min_value, tracker);
+        if tracker.stopped_by().is_some() {
+            return state.cliques.split_off(before)
+        }
+        p.remove(&node);
         x.insert(node);
+        state.processed += 1;
+        if after_vertex(state, tracker).is_break() {
+            break
         }
     }
+    Vec::new()
+}
 
 #[cfg(test)]
 mod tests {
@@ -99,7 +339,7 @@ use super::*;
         for (node, dest) in input.iter() {
             graph
                 .entry(*node)
-                .or_insert_with(HashSet::new)
+                .or_default()
                 .insert(*dest);
         }
 
@@ -123,7 +363,9 @@ use super::*;
         
         println!("my_cliques: {:?}", my_cliques);
         println!("checking_cliques: {:?}", checking_cliques);
-        assert_eq!(my_cliques.sort(), checking_cliques.sort())
+        my_cliques.sort();
+        checking_cliques.sort();
+        assert_eq!(my_cliques, checking_cliques)
 
     }
 
@@ -131,15 +373,18 @@ use super::*;
     #[test]
     fn test_alg_largescale() {
         use crate::copied_alg;
-        use crate::file_reading;
+        use crate::graph_cache;
         use std::collections::HashSet;
 
-        // Handle the Result returned by csv_to_hashmap
-        let graph_result = file_reading::csv_to_hashmap(
+        // Handle the Result returned by load_region_cached, caching into a temporary folder so the test leaves no files behind
+        let cache_dir = tempfile::tempdir().unwrap();
+        let graph_result = graph_cache::load_region_cached(
             "../twitch_data/ENGB/musae_ENGB_edges.csv",
+            "../twitch_data/ENGB/musae_ENGB_target.csv",
+            cache_dir.path().to_str().unwrap(),
             );
         let graph = match graph_result {
-            Ok(graph) => graph, // If successful, extract the graph
+            Ok(region) => region.graph, // If successful, extract the graph
             Err(err) => {
                 panic!("Error reading graph: {}", err); // Handle the error
             }
@@ -174,4 +419,27 @@ use super::*;
         assert_eq!(graph_start, graph_end, "Graph is mutated");
         assert_eq!(my_cliques, checking_cliques, "Lengths do not match");
     }
-}
\ No newline at end of file
+
+    /// Budgets should stop the search early with maximal cliques found so far, and an unlimited budget should find them all
+    #[test]
+    fn test_search_budget() {
+        use crate::random_graphs;
+
+        let graph = random_graphs::moon_moser(15); // 243 maximal cliques of 5 nodes
+        let all = maximal_cliques_within(&graph, 1, SearchBudget::default());
+        assert!(all.is_complete());
+        assert_eq!(all.cliques, maximal_cliques(&graph, 1));
+        assert_eq!(all.cliques.len(), 243);
+
+        let limited = maximal_cliques_within(&graph, 1, SearchBudget { max_cliques: Some(10), ..SearchBudget::default() });
+        assert_eq!((limited.stopped_by, limited.cliques.len()), (Some(BudgetLimit::Cliques), 10));
+        assert!(limited.cliques.iter().all(|clique| all.cliques.contains(clique)), "Partial results should still be maximal cliques");
+
+        let limited = maximal_cliques_within(&graph, 1, SearchBudget { max_calls: Some(50), ..SearchBudget::default() });
+        assert_eq!((limited.stopped_by, limited.calls), (Some(BudgetLimit::Calls), 50));
+        assert!(limited.cliques.len() < 50);
+
+        let limited = maximal_cliques_within(&graph, 1, SearchBudget { time_limit: Some(Duration::ZERO), ..SearchBudget::default() });
+        assert_eq!((limited.stopped_by, limited.cliques.len()), (Some(BudgetLimit::Time), 0));
+    }
+}
@@ -0,0 +1,211 @@
+//! Module for checkpointing long clique searches to disk, so a search that crashes or runs out of budget can resume where it left off
+use std::collections::{HashMap, HashSet};
+use std::ops::ControlFlow;
+use std::time::{Duration, Instant};
+use crate::bron_kerbosch::{self, BudgetTracker, PartialCliques, SearchBudget, SearchState};
+use crate::error::ReadError;
+use crate::graph_cache::Cursor;
+use crate::progress::ProgressEvent;
+
+const MAGIC: &[u8; 8] = b"TWCKPOIN";
+const VERSION: u32 = 1; // Raise whenever the layout below changes, so older checkpoints are ignored
+
+/// Where a search writes its checkpoint, and how often
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckpointConfig {
+    pub path: String,
+    pub interval: Duration, // Time between checkpoints, which are only written between top-level vertices, so a slow vertex can go longer
+}
+
+/// Identifies a search by its graph and minimum clique size, so a checkpoint is only ever resumed by the same search \
+/// **Output:** 64-bit FNV-1a hash of min_value and every node's sorted neighbors, in node order
+pub(crate) fn search_fingerprint(graph: &HashMap<u32, HashSet<u32>>, min_value: u32) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    let mut add = |value: u32| {
+        for byte in value.to_le_bytes() {
+            hash = (hash ^ byte as u64).wrapping_mul(0x100000001b3);
+        }
+    };
+    add(min_value);
+    let mut nodes: Vec<&u32> = graph.keys().collect();
+    nodes.sort();
+    for node in nodes {
+        let mut neighbors: Vec<&u32> = graph[node].iter().collect();
+        neighbors.sort();
+        add(*node);
+        add(neighbors.len() as u32);
+        neighbors.into_iter().for_each(|neighbor| add(*neighbor));
+    }
+    hash
+}
+
+/// Path of the checkpoint file for a search, inside checkpoint_dir, such as clique_checkpoints/cliques_0123456789abcdef.bin
+pub fn checkpoint_path(checkpoint_dir: &str, graph: &HashMap<u32, HashSet<u32>>, min_value: u32) -> String {
+    format!("{}/cliques_{:016x}.bin", checkpoint_dir, search_fingerprint(graph, min_value))
+}
+
+/// Writes a search's state to path, recording elapsed as the time spent on the search so far \
+/// The file is written then renamed, so a crash while writing leaves the previous checkpoint in place \
+/// ### Layout
+/// MAGIC, VERSION, fingerprint, elapsed nanoseconds \
+/// Candidate count and ids, processed count \
+/// Clique count, then for each clique: size and ids
+pub(crate) fn save_checkpoint(path: &str, fingerprint: u64, state: &SearchState<u32>, elapsed: Duration) -> Result<(), ReadError> {
+    let mut bytes: Vec<u8> = MAGIC.to_vec();
+    bytes.extend(VERSION.to_le_bytes());
+    bytes.extend(fingerprint.to_le_bytes());
+    bytes.extend((elapsed.as_nanos() as u64).to_le_bytes());
+    bytes.extend((state.candidates.len() as u32).to_le_bytes());
+    for candidate in &state.candidates {
+        bytes.extend(candidate.to_le_bytes());
+    }
+    bytes.extend((state.processed as u32).to_le_bytes());
+    bytes.extend((state.cliques.len() as u32).to_le_bytes());
+    for clique in &state.cliques {
+        bytes.extend((clique.len() as u32).to_le_bytes());
+        for node in clique {
+            bytes.extend(node.to_le_bytes());
+        }
+    }
+
+    if let Some(dir) = std::path::Path::new(path).parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir).map_err(|source| ReadError::Io { path: dir.display().to_string(), source })?;
+    }
+    let temp_path = format!("{}.tmp", path);
+    std::fs::write(&temp_path, bytes)
+        .and_then(|_| std::fs::rename(&temp_path, path))
+        .map_err(|source| ReadError::Io { path: path.to_string(), source })
+}
+
+/// Reads the checkpoint at path, if it was written by this search \
+/// **Output:** SearchState to pass to maximal_cliques_checkpointed, or None if the file is missing, truncated,
+/// from another layout, or from a different graph or min_value
+pub fn load_checkpoint(path: &str, graph: &HashMap<u32, HashSet<u32>>, min_value: u32) -> Option<SearchState<u32>> {
+    let bytes = std::fs::read(path).ok()?;
+    let mut cursor = Cursor { bytes: &bytes, pos: 0 };
+    if cursor.take(MAGIC.len())? != MAGIC || cursor.u32()? != VERSION || cursor.u64()? != search_fingerprint(graph, min_value) {
+        return None
+    }
+    let elapsed_before = Duration::from_nanos(cursor.u64()?);
+    let candidates = (0..cursor.u32()?).map(|_| cursor.u32()).collect::<Option<Vec<u32>>>()?;
+    let processed = cursor.u32()? as usize;
+    let mut cliques = Vec::new();
+    for _ in 0..cursor.u32()? {
+        let size = cursor.u32()?;
+        cliques.push((0..size).map(|_| cursor.u32()).collect::<Option<Vec<u32>>>()?);
+    }
+    if cursor.pos != bytes.len() || processed > candidates.len() { // Trailing bytes or an impossible count mean the file wasn't written by this layout
+        return None
+    }
+    Some(SearchState { candidates, processed, cliques, elapsed_before })
+}
+
+/// Finds maximal cliques like bron_kerbosch::maximal_cliques_with_progress, writing checkpoints as it goes \
+/// ### Inputs
+/// graph, min_value, budget, on_progress: as for maximal_cliques_with_progress \
+/// config: where to write checkpoints and how often \
+/// resume: state from load_checkpoint to continue from, or None to start a new search \
+/// ### Checkpoint Logic
+/// After each top-level vertex, a checkpoint is written if config.interval has passed since the last one \
+/// Work inside a top-level vertex isn't saved, so a crash loses everything since the last finished vertex,
+/// which on the larger regions can be hours for a single high degree vertex \
+/// If a checkpoint can't be written the search stops straight away, rather than running on without checkpoints \
+/// If the budget stops the search, a final checkpoint is written so a later run can continue it \
+/// Once the search finishes the checkpoint is deleted, since it is no longer needed \
+/// ### Output
+/// PartialCliques, where a finished search has exactly the cliques of an uninterrupted run, and elapsed includes earlier runs \
+/// Or a ReadError from the first checkpoint that can't be written
+pub fn maximal_cliques_checkpointed(
+    graph: &HashMap<u32, HashSet<u32>>,
+    min_value: u32,
+    budget: SearchBudget,
+    config: &CheckpointConfig,
+    resume: Option<SearchState<u32>>,
+    on_progress: &mut dyn FnMut(&ProgressEvent),
+) -> Result<PartialCliques<u32>, ReadError> {
+    let fingerprint = search_fingerprint(graph, min_value);
+    let mut state = resume.unwrap_or_else(|| SearchState::new(graph));
+    let mut tracker = BudgetTracker::new(budget);
+    let mut last_save = Instant::now();
+    let mut saved = Ok(());
+
+    let unfinished = bron_kerbosch::continue_search(graph, min_value, &mut state, &mut tracker, &mut |state, tracker| {
+        on_progress(&state.progress(tracker));
+        if last_save.elapsed() >= config.interval {
+            saved = save_checkpoint(&config.path, fingerprint, state, state.elapsed(tracker));
+            last_save = Instant::now();
+        }
+        if saved.is_ok() { ControlFlow::Continue(()) } else { ControlFlow::Break(()) }
+    });
+    saved?;
+
+    if state.is_finished() {
+        match std::fs::remove_file(&config.path) {
+            Err(source) if source.kind() != std::io::ErrorKind::NotFound => return Err(ReadError::Io { path: config.path.clone(), source }),
+            _ => (),
+        }
+    } else {
+        save_checkpoint(&config.path, fingerprint, &state, state.elapsed(&tracker))?;
+    }
+    Ok(state.finish(unfinished, &tracker))
+}
+
+#[cfg(test)]
+mod tests {
+use super::*;
+    use crate::random_graphs;
+
+    /// A search stopped over and over by its budget, resuming from its checkpoint each time, should end with the same cliques as one run \
+    /// A checkpoint that can't be written should stop the search
+    #[test]
+    fn test_resume() {
+        let graph = random_graphs::barabasi_albert(300, 6, 4);
+        let expected = bron_kerbosch::maximal_cliques(&graph, 3);
+        let dir = tempfile::tempdir().unwrap();
+        let config = CheckpointConfig { path: checkpoint_path(dir.path().to_str().unwrap(), &graph, 3), interval: Duration::ZERO };
+        let budget = SearchBudget { max_calls: Some(300), ..SearchBudget::default() };
+
+        let mut runs = 0;
+        let found = loop {
+            let resume = load_checkpoint(&config.path, &graph, 3);
+            assert_eq!(resume.is_some(), runs > 0, "Every stopped run should leave a checkpoint");
+            let found = maximal_cliques_checkpointed(&graph, 3, budget, &config, resume, &mut |_| ()).unwrap();
+            runs += 1;
+            assert!(runs < 1000, "Each run should finish at least one top-level vertex");
+            if found.is_complete() {
+                break found
+            }
+        };
+        assert!(runs > 3, "The budget should stop the search several times, only {} runs", runs);
+        assert_eq!(found.cliques, expected);
+        assert!(!std::path::Path::new(&config.path).exists(), "A finished search should delete its checkpoint");
+
+        let blocker = tempfile::NamedTempFile::new().unwrap(); // A file where the checkpoint folder should be, so every save fails
+        let config = CheckpointConfig { path: format!("{}/search.bin", blocker.path().display()), interval: Duration::ZERO };
+        let mut vertices = 0;
+        assert!(maximal_cliques_checkpointed(&graph, 3, SearchBudget::default(), &config, None, &mut |_| vertices += 1).is_err());
+        assert_eq!(vertices, 1, "The search should stop at the first failed checkpoint");
+    }
+
+    /// Checkpoints should only load for the same graph and min_value, and never when damaged
+    #[test]
+    fn test_checkpoint_matching() {
+        let graph = random_graphs::erdos_renyi(40, 0.3, 1);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("search.bin").to_str().unwrap().to_string();
+        let mut state = SearchState::new(&graph);
+        state.processed = 2;
+        state.cliques = vec![vec![1, 2, 3], vec![4, 5]];
+        save_checkpoint(&path, search_fingerprint(&graph, 2), &state, Duration::from_secs(5)).unwrap();
+
+        let loaded = load_checkpoint(&path, &graph, 2).unwrap();
+        assert_eq!(loaded, SearchState { elapsed_before: Duration::from_secs(5), ..state });
+        assert_eq!(load_checkpoint(&path, &graph, 3), None, "A different min_value is a different search");
+        assert_eq!(load_checkpoint(&path, &random_graphs::erdos_renyi(40, 0.3, 2), 2), None, "A different graph is a different search");
+        assert_ne!(checkpoint_path("dir", &graph, 2), checkpoint_path("dir", &graph, 3));
+
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &bytes[..bytes.len() - 2]).unwrap();
+        assert_eq!(load_checkpoint(&path, &graph, 2), None, "A truncated checkpoint shouldn't load");
+    }
+}
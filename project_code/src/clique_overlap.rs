@@ -0,0 +1,152 @@
+//! Module for the clique overlap graph, where each maximal clique is a vertex and edges are weighted by shared members
+use std::collections::HashMap;
+use plotters::prelude::*;
+use crate::clique_percolation::find_root;
+
+/// Builds the clique overlap graph from cliques found by run_bron_kerbosch \
+/// **Input:** vector of sorted u32 vectors, the index of each clique is its vertex in the overlap graph \
+/// Counts the members shared between each pair of cliques using a node -> cliques index,
+/// so pairs of cliques with nothing in common are never compared \
+/// **Output:** vector of edges (clique index a, clique index b, Jaccard similarity) with a < b, sorted by clique indices \
+/// Jaccard similarity is shared members divided by the size of the union of both cliques
+pub fn clique_overlap_graph(cliques: &[Vec<u32>]) -> Vec<(usize, usize, f32)> {
+    let mut cliques_by_node: HashMap<u32, Vec<usize>> = HashMap::new(); // Lists the cliques each node belongs to
+    for (idx, clique) in cliques.iter().enumerate() {
+        for node in clique {
+            cliques_by_node.entry(*node).or_default().push(idx);
+        }
+    }
+
+    let mut edges = Vec::new();
+    for (idx, clique) in cliques.iter().enumerate() {
+        let mut shared_counts: HashMap<usize, usize> = HashMap::new(); // Counts members shared with each later clique
+        for node in clique {
+            for other in cliques_by_node[node].iter().filter(|other| **other > idx) {
+                *shared_counts.entry(*other).or_insert(0) += 1;
+            }
+        }
+        let mut clique_edges: Vec<(usize, usize, f32)> = shared_counts
+            .into_iter()
+            .map(|(other, shared)| {
+                let union = clique.len() + cliques[other].len() - shared;
+                (idx, other, shared as f32 / union as f32)
+            })
+            .collect();
+        clique_edges.sort_by_key(|edge| edge.1);
+        edges.extend(clique_edges);
+    }
+    edges
+}
+
+/// Summary statistics of a clique overlap graph
+#[derive(Debug, Clone, PartialEq)]
+pub struct OverlapSummary {
+    pub cliques: usize,
+    pub edges: usize,
+    pub density: f32,
+    pub mean_jaccard: f32,
+    pub max_jaccard: f32,
+    pub isolated_cliques: usize,
+    pub components: usize,
+    pub largest_component: usize,
+}
+
+/// Summarizes the clique overlap graph \
+/// **Input:** number of cliques and the edges from clique_overlap_graph \
+/// **Output:** OverlapSummary with the edge count, density, mean and max Jaccard similarity,
+/// cliques that share no members with any other clique, and the number and largest size of connected components
+pub fn overlap_summary(clique_count: usize, edges: &[(usize, usize, f32)]) -> OverlapSummary {
+    let mut degrees = vec![0usize; clique_count];
+    let mut parents: Vec<usize> = (0..clique_count).collect();
+    for (a, b, _) in edges {
+        degrees[*a] += 1;
+        degrees[*b] += 1;
+        let root_a = find_root(&mut parents, *a);
+        let root_b = find_root(&mut parents, *b);
+        parents[root_a.max(root_b)] = root_a.min(root_b);
+    }
+
+    let mut component_sizes: HashMap<usize, usize> = HashMap::new();
+    for idx in 0..clique_count {
+        *component_sizes.entry(find_root(&mut parents, idx)).or_insert(0) += 1;
+    }
+
+    let possible_edges = clique_count * clique_count.saturating_sub(1) / 2;
+    let weight_sum: f32 = edges.iter().map(|edge| edge.2).sum();
+    OverlapSummary {
+        cliques: clique_count,
+        edges: edges.len(),
+        density: if possible_edges == 0 { 0.0 } else { edges.len() as f32 / possible_edges as f32 },
+        mean_jaccard: if edges.is_empty() { 0.0 } else { weight_sum / edges.len() as f32 },
+        max_jaccard: edges.iter().map(|edge| edge.2).fold(0.0, f32::max),
+        isolated_cliques: degrees.iter().filter(|degree| **degree == 0).count(),
+        components: component_sizes.len(),
+        largest_component: component_sizes.values().cloned().max().unwrap_or(0),
+    }
+}
+
+/// Uses plotters to draw a heatmap of the Jaccard similarity between every pair of cliques \
+/// **Input:** number of cliques to draw, the edges from clique_overlap_graph and the output filename \
+/// Only the first clique_count cliques are drawn, edges to later cliques are skipped \
+/// Cell (i, j) is shaded from white (no shared members) to red (identical cliques), the diagonal is always red \
+/// **Output:** heatmap written to the given .png file in the project_code folder \
+///
+/// **Note** \
+/// Every pair of cliques gets a cell, so the chart becomes unreadable past a few hundred cliques
+pub fn plot_overlap_heatmap(clique_count: usize, edges: &[(usize, usize, f32)], filename: &str) {
+    let mut matrix = vec![vec![0f32; clique_count]; clique_count];
+    for (idx, row) in matrix.iter_mut().enumerate() {
+        row[idx] = 1.0;
+    }
+    for (a, b, weight) in edges.iter().filter(|edge| edge.1 < clique_count) { // Mirror each edge, the overlap graph is undirected
+        matrix[*a][*b] = *weight;
+        matrix[*b][*a] = *weight;
+    }
+
+    let root_area = BitMapBackend::new(filename, (1024, 1024))
+        .into_drawing_area();
+    root_area.fill(&WHITE).unwrap();
+
+    let mut chart = ChartBuilder::on(&root_area)
+        .caption("Clique Overlap (Jaccard Similarity)", ("Arial", 20).into_font())
+        .x_label_area_size(40)
+        .y_label_area_size(40)
+        .margin(10)
+        .build_cartesian_2d(0..clique_count, 0..clique_count).unwrap();
+
+    chart.configure_mesh()
+        .disable_mesh()
+        .x_desc("Clique")
+        .y_desc("Clique")
+        .draw()
+        .unwrap();
+
+    chart.draw_series(matrix.iter().enumerate().flat_map(|(i, row)| {
+        row.iter().enumerate().map(move |(j, weight)| {
+            let fade = (255.0 * (1.0 - weight)) as u8; // Higher overlap fades green and blue out, leaving red
+            Rectangle::new([(i, j), (i + 1, j + 1)], RGBColor(255, fade, fade).filled())
+        })
+    }))
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+use super::*;
+
+    /// Tests the overlap graph and summary against hand-computed Jaccard values
+    #[test]
+    fn test_overlap_graph() {
+        let cliques = vec![vec![1, 2, 3], vec![2, 3, 4, 5], vec![5, 6], vec![7, 8]];
+
+        let edges = clique_overlap_graph(&cliques);
+        assert_eq!(edges, vec![(0, 1, 2.0 / 5.0), (1, 2, 1.0 / 5.0)]);
+
+        let summary = overlap_summary(cliques.len(), &edges);
+        assert_eq!(summary.edges, 2);
+        assert_eq!(summary.isolated_cliques, 1);
+        assert_eq!(summary.components, 2);
+        assert_eq!(summary.largest_component, 3);
+        assert_eq!(summary.max_jaccard, 0.4);
+    }
+}
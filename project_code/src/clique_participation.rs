@@ -0,0 +1,130 @@
+//! Module for the clique participation index, counting how many maximal cliques each node belongs to
+use std::collections::HashMap;
+use plotters::prelude::*;
+use crate::data_analysis::{NodeStats, pearson_correlation, spearman_correlation};
+
+/// Clique participation of a single node
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Participation {
+    pub clique_count: usize,
+    pub largest_clique: usize,
+}
+
+/// Finds the clique participation index of every node in the given cliques \
+/// **Input:** vector of u32 vectors, such as the output of run_bron_kerbosch \
+/// **Output:** HashMap of node ids to the number of cliques containing the node and the size of the largest one \
+/// Nodes in no clique are left out, and should be treated as a participation of 0
+pub fn clique_participation(cliques: &[Vec<u32>]) -> HashMap<u32, Participation> {
+    let mut participation: HashMap<u32, Participation> = HashMap::new();
+    for clique in cliques {
+        for node in clique {
+            let entry = participation.entry(*node).or_insert(Participation { clique_count: 0, largest_clique: 0 });
+            entry.clique_count += 1;
+            entry.largest_clique = entry.largest_clique.max(clique.len());
+        }
+    }
+    participation
+}
+
+/// Correlations between one participation measure and the NodeStats fields
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ParticipationCorrelations {
+    pub views_pearson: f64,
+    pub log_views_pearson: f64,
+    pub views_spearman: f64,
+    pub partner: f64,
+    pub mature: f64,
+}
+
+/// Correlates a participation measure with views, partner and mature for every node in the target file \
+/// **Input:** participation HashMap, NodeStats HashMap (such as from load_target_stats)
+/// and a function picking the measure, such as |p| p.clique_count \
+/// Nodes with no participation entry count as 0 \
+/// **Output:** ParticipationCorrelations, where partner and mature are point-biserial correlations and
+/// log views uses ln(views + 1) to dampen the most viewed streamers, or None if stats holds fewer than 2 nodes
+pub fn participation_correlations(
+    participation: &HashMap<u32, Participation>,
+    stats: &HashMap<u32, NodeStats>,
+    measure: fn(&Participation) -> usize
+) -> Option<ParticipationCorrelations> {
+    let mut node_ids: Vec<&u32> = stats.keys().collect();
+    node_ids.sort();
+
+    let measures: Vec<f64> = node_ids.iter()
+        .map(|id| participation.get(id).map(measure).unwrap_or(0) as f64)
+        .collect();
+    let views: Vec<f64> = node_ids.iter().map(|id| stats[id].views as f64).collect();
+    let log_views: Vec<f64> = views.iter().map(|views| views.ln_1p()).collect();
+    let partner: Vec<f64> = node_ids.iter().map(|id| if stats[id].partner { 1.0 } else { 0.0 }).collect();
+    let mature: Vec<f64> = node_ids.iter().map(|id| if stats[id].mature { 1.0 } else { 0.0 }).collect();
+
+    Some(ParticipationCorrelations {
+        views_pearson: pearson_correlation(&measures, &views)?,
+        log_views_pearson: pearson_correlation(&measures, &log_views)?,
+        views_spearman: spearman_correlation(&measures, &views)?,
+        partner: pearson_correlation(&measures, &partner)?,
+        mature: pearson_correlation(&measures, &mature)?,
+    })
+}
+
+/// Uses plotters to draw a scatter chart of clique participation against views \
+/// **Input:** participation HashMap, NodeStats HashMap and the output filename \
+/// Only nodes in at least one clique are drawn, views use a log scale since they span several orders of magnitude \
+/// Partners are drawn in red and non-partners in blue \
+/// **Output:** scatter chart written to the given .png file in the project_code folder
+pub fn plot_participation_scatter(participation: &HashMap<u32, Participation>, stats: &HashMap<u32, NodeStats>, filename: &str) {
+    let points: Vec<(usize, f64, bool)> = participation.iter()
+        .filter_map(|(id, part)| stats.get(id).map(|node| (part.clique_count, node.views as f64 + 1.0, node.partner))) // Shift views by 1 so 0 fits on the log scale
+        .collect();
+    let max_count = points.iter().map(|point| point.0).max().unwrap_or(0) + 1;
+    let max_views = points.iter().map(|point| point.1).fold(10.0, f64::max) * 2.0;
+
+    let root_area = BitMapBackend::new(filename, (1024, 768))
+        .into_drawing_area();
+    root_area.fill(&WHITE).unwrap();
+
+    let mut chart = ChartBuilder::on(&root_area)
+        .caption("Clique Participation vs Views", ("Arial", 20).into_font())
+        .x_label_area_size(40)
+        .y_label_area_size(60)
+        .margin(10)
+        .build_cartesian_2d(0..max_count, (1f64..max_views).log_scale()).unwrap();
+
+    chart.configure_mesh()
+        .x_desc("Number of Maximal Cliques")
+        .y_desc("Views + 1")
+        .y_label_formatter(&|y| format!("{}", *y as u64)) // Drop the decimals from the log scale labels
+        .draw()
+        .unwrap();
+
+    chart.draw_series(points.iter().map(|(count, views, partner)| {
+        let color = if *partner { RED } else { BLUE };
+        Circle::new((*count, *views), 3, color.mix(0.5).filled())
+    }))
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+use super::*;
+
+    /// Tests the participation counts and that more participation tracks more views
+    #[test]
+    fn test_participation() {
+        let cliques = vec![vec![1, 2, 3, 4], vec![1, 2, 5], vec![1, 6]];
+        let participation = clique_participation(&cliques);
+
+        assert_eq!(participation[&1], Participation { clique_count: 3, largest_clique: 4 });
+        assert_eq!(participation[&5], Participation { clique_count: 1, largest_clique: 3 });
+        assert!(!participation.contains_key(&7));
+
+        let mut stats = HashMap::new();
+        for (id, views) in [(1, 900), (2, 500), (3, 100), (4, 120), (5, 80), (6, 90), (7, 10)] {
+            stats.insert(id, NodeStats { id: id + 1000, new_id: id, views, days: 100, mature: false, partner: id == 1 });
+        }
+        let correlations = participation_correlations(&participation, &stats, |part| part.clique_count).unwrap();
+        assert!(correlations.views_spearman > 0.5, "Participation should rise with views");
+        assert!(correlations.partner > 0.5, "The only partner is in the most cliques");
+        assert_eq!(correlations.mature, 0.0, "No variance in mature");
+    }
+}
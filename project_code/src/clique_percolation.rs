@@ -0,0 +1,187 @@
+//! Module for the clique percolation method, which merges overlapping maximal cliques into k-clique communities
+use std::collections::{HashMap, HashSet};
+use crate::data_analysis::NodeStats;
+
+/// Runs the ['Clique Percolation Method'] on cliques found by run_bron_kerbosch \
+/// ### Inputs
+/// cliques: vector of sorted u32 vectors, such as the output of run_bron_kerbosch \
+/// k: size of the k-cliques being percolated, cliques smaller than k are ignored \
+/// ### Algorithm Logic
+/// Two cliques of at least size k are adjacent if they share at least k - 1 nodes \
+/// Adjacent cliques are merged with a union-find structure \
+/// Each connected group of cliques becomes a community containing the union of their nodes \
+/// ### Output
+/// Vector of sorted u32 vectors, one per community, sorted for consistent output \
+/// None if k is below 2, since cliques sharing k - 1 = 0 nodes would all merge into meaningless communities
+///
+/// **Note** \
+/// Communities are only complete if every maximal clique of size k or more was given,
+/// so run_bron_kerbosch must be run with a min_value of at most k
+///
+/// ['Clique Percolation Method']: https://en.wikipedia.org/wiki/Clique_percolation_method
+pub fn clique_percolation(cliques: &[Vec<u32>], k: usize) -> Option<Vec<Vec<u32>>> {
+    if k < 2 {
+        return None
+    }
+    let large_cliques: Vec<&Vec<u32>> = cliques.iter().filter(|clique| clique.len() >= k).collect();
+    let mut parents: Vec<usize> = (0..large_cliques.len()).collect();
+
+    let mut cliques_by_node: HashMap<u32, Vec<usize>> = HashMap::new(); // Lists the cliques each node belongs to
+    for (idx, clique) in large_cliques.iter().enumerate() {
+        for node in clique.iter() {
+            cliques_by_node.entry(*node).or_default().push(idx);
+        }
+    }
+
+    for (idx, clique) in large_cliques.iter().enumerate() {
+        let mut shared_counts: HashMap<usize, usize> = HashMap::new(); // Counts nodes shared with each later clique
+        for node in clique.iter() {
+            for other in cliques_by_node[node].iter().filter(|other| **other > idx) {
+                *shared_counts.entry(*other).or_insert(0) += 1;
+            }
+        }
+        for (other, shared) in shared_counts {
+            if shared + 1 >= k { // Cliques sharing k - 1 nodes percolate into each other
+                let root_a = find_root(&mut parents, idx);
+                let root_b = find_root(&mut parents, other);
+                parents[root_a.max(root_b)] = root_a.min(root_b);
+            }
+        }
+    }
+
+    let mut grouped_nodes: HashMap<usize, HashSet<u32>> = HashMap::new();
+    for (idx, clique) in large_cliques.iter().enumerate() {
+        let root = find_root(&mut parents, idx);
+        grouped_nodes.entry(root).or_default().extend(clique.iter().cloned());
+    }
+
+    let mut communities: Vec<Vec<u32>> = grouped_nodes
+        .into_values()
+        .map(|nodes| {
+            let mut community: Vec<u32> = nodes.into_iter().collect();
+            community.sort();
+            community
+        })
+        .collect();
+    communities.sort();
+    Some(communities)
+}
+
+/// Finds the representative of a union-find group, compressing the path along the way \
+/// Shared with the clique_overlap module, which groups cliques into connected components the same way
+pub(crate) fn find_root(parents: &mut [usize], idx: usize) -> usize {
+    let mut root = idx;
+    while parents[root] != root {
+        root = parents[root];
+    }
+    let mut current = idx;
+    while parents[current] != root { // Point every clique on the path directly at the root
+        let next = parents[current];
+        parents[current] = root;
+        current = next;
+    }
+    root
+}
+
+/// Reports the communities each node is a member of \
+/// **Input:** vector of communities such as the output of clique_percolation \
+/// **Output:** HashMap of node ids to the indices of every community containing that node \
+/// Nodes found in more than one community are the overlaps between communities
+pub fn node_memberships(communities: &[Vec<u32>]) -> HashMap<u32, Vec<usize>> {
+    let mut memberships: HashMap<u32, Vec<usize>> = HashMap::new();
+    for (idx, community) in communities.iter().enumerate() {
+        for node in community {
+            memberships.entry(*node).or_default().push(idx);
+        }
+    }
+    memberships
+}
+
+/// Viewership statistics for a single k-clique community
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommunityStats {
+    pub community: usize,
+    pub size: usize,
+    pub total_views: u64,
+    pub mean_views: f64,
+    pub median_views: f64,
+    pub top_share: f64,
+    pub partner_count: usize,
+    pub mature_count: usize,
+}
+
+/// Summarizes the viewership of each community \
+/// **Input:** vector of communities and a HashMap of node ids to NodeStats, such as from load_target_stats \
+/// Nodes missing from the HashMap are skipped \
+/// **Output:** vector of CommunityStats, where top_share is the largest single node's % of the community's total views
+pub fn community_viewership_stats(communities: &[Vec<u32>], stats: &HashMap<u32, NodeStats>) -> Vec<CommunityStats> {
+    let mut all_stats = Vec::new();
+    for (idx, community) in communities.iter().enumerate() {
+        let members: Vec<&NodeStats> = community.iter().filter_map(|node| stats.get(node)).collect();
+        let mut views: Vec<u64> = members.iter().map(|node| node.views as u64).collect();
+        views.sort();
+
+        let total_views: u64 = views.iter().sum();
+        let mean_views = if views.is_empty() { 0.0 } else { total_views as f64 / views.len() as f64 };
+        let median_views = match views.len() {
+            0 => 0.0,
+            len if len % 2 == 0 => (views[len / 2 - 1] + views[len / 2]) as f64 / 2.0,
+            len => views[len / 2] as f64,
+        };
+        let top_share = match views.last() {
+            Some(max) if total_views > 0 => *max as f64 / total_views as f64,
+            _ => 0.0,
+        };
+
+        all_stats.push(CommunityStats {
+            community: idx,
+            size: community.len(),
+            total_views,
+            mean_views,
+            median_views,
+            top_share,
+            partner_count: members.iter().filter(|node| node.partner).count(),
+            mature_count: members.iter().filter(|node| node.mature).count(),
+        });
+    }
+    all_stats
+}
+
+#[cfg(test)]
+mod tests {
+use super::*;
+
+    /// Two triangles sharing an edge merge for k = 3, a third triangle sharing one node does not
+    #[test]
+    fn test_percolation() {
+        let cliques = vec![vec![1, 2, 3], vec![2, 3, 4], vec![4, 5, 6], vec![7, 8]];
+
+        let communities = clique_percolation(&cliques, 3).unwrap();
+        assert_eq!(communities, vec![vec![1, 2, 3, 4], vec![4, 5, 6]]);
+
+        let memberships = node_memberships(&communities);
+        assert_eq!(memberships[&4], vec![0, 1], "Overlapping node should belong to both communities");
+        assert_eq!(memberships[&1], vec![0]);
+        assert!(!memberships.contains_key(&7), "Cliques smaller than k should be ignored");
+
+        let merged = clique_percolation(&cliques, 2).unwrap();
+        assert_eq!(merged, vec![vec![1, 2, 3, 4, 5, 6], vec![7, 8]]);
+        assert_eq!(clique_percolation(&cliques, 1), None, "k below 2 should be rejected");
+        assert_eq!(clique_percolation(&cliques, 0), None);
+    }
+
+    /// Tests the community statistics against hand-computed values
+    #[test]
+    fn test_community_stats() {
+        let mut stats = HashMap::new();
+        for (id, views, partner) in [(1, 100, true), (2, 300, false), (3, 600, false)] {
+            stats.insert(id, NodeStats { id: id + 1000, new_id: id, views, days: 100, mature: false, partner });
+        }
+        let community_stats = community_viewership_stats(&[vec![1, 2, 3]], &stats);
+
+        assert_eq!(community_stats[0].total_views, 1000);
+        assert_eq!(community_stats[0].median_views, 300.0);
+        assert_eq!(community_stats[0].top_share, 0.6);
+        assert_eq!(community_stats[0].partner_count, 1);
+    }
+}
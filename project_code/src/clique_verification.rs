@@ -0,0 +1,156 @@
+//! Module for checking the cliques reported by the Bron-Kerbosch algorithm at runtime, rather than only in tests
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Largest graph, in nodes, that brute_force_cliques will search, since it checks every subset of nodes
+pub const BRUTE_FORCE_LIMIT: usize = 20;
+
+/// Problems found by verify_cliques, listing cliques by their index in the checked list
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct VerificationReport {
+    pub cliques_checked: usize,
+    pub incomplete: Vec<usize>,
+    pub not_maximal: Vec<usize>,
+    pub duplicates: Vec<usize>,
+    pub too_small: Vec<usize>,
+    pub missing: Vec<Vec<u32>>, // Cliques found by brute force but not reported, only filled by check_against_brute_force
+}
+
+impl VerificationReport {
+    /// True when every clique passed every check
+    pub fn is_valid(&self) -> bool {
+        self.incomplete.is_empty()
+            && self.not_maximal.is_empty()
+            && self.duplicates.is_empty()
+            && self.too_small.is_empty()
+            && self.missing.is_empty()
+    }
+}
+
+impl fmt::Display for VerificationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_valid() {
+            return write!(f, "all {} cliques are complete, maximal and unique", self.cliques_checked)
+        }
+        write!(f, "{} of {} cliques are not complete, {} are not maximal, {} are duplicates, {} are below the minimum size and {} cliques are missing",
+            self.incomplete.len(), self.cliques_checked, self.not_maximal.len(), self.duplicates.len(), self.too_small.len(), self.missing.len())
+    }
+}
+
+impl std::error::Error for VerificationReport {}
+
+/// Checks that every reported clique is a maximal clique of the graph, and that none is reported twice \
+/// ### Checks
+/// incomplete: some pair of members isn't connected, or a member isn't in the graph \
+/// not_maximal: a node outside the clique is connected to every member, so the clique could grow \
+/// duplicates: the clique has the same members as an earlier clique, in any order \
+/// too_small: the clique has fewer than min_value members \
+/// ### Output
+/// VerificationReport, where missing is always empty, see check_against_brute_force
+pub fn verify_cliques(graph: &HashMap<u32, HashSet<u32>>, cliques: &[Vec<u32>], min_value: u32) -> VerificationReport {
+    let empty = HashSet::new();
+    let mut report = VerificationReport { cliques_checked: cliques.len(), ..VerificationReport::default() };
+    let mut seen: HashSet<Vec<u32>> = HashSet::new();
+
+    for (idx, clique) in cliques.iter().enumerate() {
+        let mut members = clique.clone();
+        members.sort();
+        if !seen.insert(members) {
+            report.duplicates.push(idx);
+        }
+        if clique.len() < min_value as usize {
+            report.too_small.push(idx);
+        }
+
+        let neighbor_sets: Vec<&HashSet<u32>> = clique.iter().map(|node| graph.get(node).unwrap_or(&empty)).collect();
+        let complete = clique.iter().enumerate().all(|(a, node)| {
+            graph.contains_key(node) && clique[a + 1..].iter().all(|other| neighbor_sets[a].contains(other))
+        });
+        if !complete {
+            report.incomplete.push(idx);
+            continue // Maximality is only meaningful for complete cliques
+        }
+
+        // A node extending the clique must be a neighbor of every member, so the smallest neighbor set is enough to search
+        let Some(smallest) = neighbor_sets.iter().min_by_key(|neighbors| neighbors.len()) else { continue };
+        let extendable = smallest.iter().any(|candidate| {
+            !clique.contains(candidate) && neighbor_sets.iter().all(|neighbors| neighbors.contains(candidate))
+        });
+        if extendable {
+            report.not_maximal.push(idx);
+        }
+    }
+    report
+}
+
+/// Finds every maximal clique of at least min_value nodes by checking every subset of nodes \
+/// Far too slow for the Twitch regions, but independent of the Bron-Kerbosch implementation \
+/// **Output:** the sorted cliques, each sorted, or None if the graph has more than BRUTE_FORCE_LIMIT nodes
+pub fn brute_force_cliques(graph: &HashMap<u32, HashSet<u32>>, min_value: u32) -> Option<Vec<Vec<u32>>> {
+    if graph.len() > BRUTE_FORCE_LIMIT {
+        return None
+    }
+    let mut nodes: Vec<u32> = graph.keys().cloned().collect();
+    nodes.sort();
+    let is_clique = |mask: u32| {
+        (0..nodes.len()).filter(|a| mask & (1 << a) != 0).all(|a| {
+            (a + 1..nodes.len()).filter(|b| mask & (1 << b) != 0).all(|b| graph[&nodes[a]].contains(&nodes[b]))
+        })
+    };
+
+    let mut cliques = Vec::new();
+    for mask in 1u32..(1 << nodes.len()) {
+        if mask.count_ones() < min_value || !is_clique(mask) {
+            continue
+        }
+        let maximal = (0..nodes.len()).all(|a| mask & (1 << a) != 0 || !is_clique(mask | (1 << a)));
+        if maximal {
+            cliques.push((0..nodes.len()).filter(|a| mask & (1 << a) != 0).map(|a| nodes[a]).collect());
+        }
+    }
+    cliques.sort();
+    Some(cliques)
+}
+
+/// Runs verify_cliques, then fills missing with any brute force clique that wasn't reported \
+/// **Output:** VerificationReport, and whether the brute force search ran (false for graphs over BRUTE_FORCE_LIMIT nodes)
+pub fn check_against_brute_force(graph: &HashMap<u32, HashSet<u32>>, cliques: &[Vec<u32>], min_value: u32) -> (VerificationReport, bool) {
+    let mut report = verify_cliques(graph, cliques, min_value);
+    let Some(expected) = brute_force_cliques(graph, min_value) else { return (report, false) };
+    let reported: HashSet<Vec<u32>> = cliques.iter().map(|clique| {
+        let mut members = clique.clone();
+        members.sort();
+        members
+    }).collect();
+    report.missing = expected.into_iter().filter(|clique| !reported.contains(clique)).collect();
+    (report, true)
+}
+
+#[cfg(test)]
+mod tests {
+use super::*;
+    use crate::bron_kerbosch;
+    use crate::random_graphs::graph_from_edges;
+
+    /// Tests that Bron-Kerbosch output passes every check, and that each kind of bad clique is caught
+    #[test]
+    fn test_verification() {
+        let graph = graph_from_edges(&[(1, 2), (1, 3), (1, 4), (2, 3), (2, 4), (3, 4), (4, 5), (5, 6), (6, 7), (5, 7), (8, 9)]);
+        let mut cliques: Vec<Vec<u32>> = Vec::new();
+        let mut p: HashSet<u32> = graph.keys().cloned().collect();
+        bron_kerbosch::run_bron_kerbosch(&HashSet::new(), &mut p, &mut HashSet::new(), &graph, &mut cliques, 2);
+
+        let (report, searched) = check_against_brute_force(&graph, &cliques, 2);
+        assert!(searched);
+        assert!(report.is_valid(), "{}", report);
+        assert_eq!(report.cliques_checked, 4);
+
+        let bad = vec![vec![1, 2, 3], vec![1, 2, 5], vec![4, 3, 2, 1], vec![1, 2, 3, 4], vec![8, 9]];
+        let (report, _) = check_against_brute_force(&graph, &bad, 3);
+        assert_eq!(report.not_maximal, vec![0]);
+        assert_eq!(report.incomplete, vec![1]);
+        assert_eq!(report.duplicates, vec![3]);
+        assert_eq!(report.too_small, vec![4]);
+        assert_eq!(report.missing, vec![vec![5, 6, 7]]);
+    }
+}
@@ -40,7 +40,7 @@ pub mod tests {
 
 
         if let Some(pivot_vertex) = pivot {
-            let neighbors = g.get(&pivot_vertex).cloned().unwrap_or_default();
+            let neighbors = g.get(pivot_vertex).cloned().unwrap_or_default();
             let candidates: Vec<u32> = p.difference(&neighbors).cloned().collect();
 
             // Sort the candidates to ensure the same order in recursion
@@ -50,7 +50,7 @@ pub mod tests {
             for v in sorted_candidates {
                 // New R is R ∪ {v}
                 let mut new_r = r.clone();
-                new_r.insert(v.clone());
+                new_r.insert(v);
 
                 // New P is P ∩ N(v)
                 let neighbors_v = g.get(&v).cloned().unwrap_or_default();
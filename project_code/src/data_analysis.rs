@@ -1,10 +1,14 @@
 //! Module focused on data manipulation and visualization.
 use serde::Deserialize;
-/// Represents relevant statistics for each node, found in target.csv files
+/// Represents relevant statistics for each node, found in target.csv files \
+/// id is the streamer's original id, a u32 Twitch id by default, or another type such as a String username
+/// when read with load_target_stats_as, while new_id is always the dense u32 id used in the graph
 #[derive(Debug, Deserialize, Copy, Clone, Hash, Eq, PartialEq)]
-pub struct NodeStats {
+pub struct NodeStats<Id = u32> {
+    pub id: Id,
     pub new_id: u32,
     pub views: u32,
+    pub days: u32,
     #[serde(deserialize_with = "deserialize_bool")] // Custom deserialize function to override capitalization
     pub mature: bool,
     #[serde(deserialize_with = "deserialize_bool")]
@@ -44,7 +48,74 @@ pub fn viewership_distribution (cliques: &Vec<Vec<NodeStats>>) -> Vec<Vec<(u32,
         }
         all_view_dists.push(clique_viewership_dist); // Pushes each clique vector to the output vector
     }
-    return all_view_dists
+    all_view_dists
+}
+
+/// Finds the Pearson correlation coefficient between two equal length samples \
+/// Bool fields can be passed as 0.0/1.0, giving the point-biserial correlation \
+/// **Output:** coefficient between -1 and 1, or 0.0 if either sample has no variance \
+/// None if the samples differ in length or hold fewer than 2 values
+pub fn pearson_correlation(xs: &[f64], ys: &[f64]) -> Option<f64> {
+    if xs.len() != ys.len() || xs.len() < 2 {
+        return None
+    }
+    let n = xs.len() as f64;
+    let mean_x = xs.iter().sum::<f64>() / n;
+    let mean_y = ys.iter().sum::<f64>() / n;
+    let mut covariance = 0.0;
+    let mut var_x = 0.0;
+    let mut var_y = 0.0;
+    for (x, y) in xs.iter().zip(ys) {
+        covariance += (x - mean_x) * (y - mean_y);
+        var_x += (x - mean_x).powi(2);
+        var_y += (y - mean_y).powi(2);
+    }
+    if var_x == 0.0 || var_y == 0.0 {
+        return Some(0.0)
+    }
+    Some(covariance / (var_x.sqrt() * var_y.sqrt()))
+}
+
+/// Finds the Spearman rank correlation coefficient, the Pearson correlation of each sample's ranks \
+/// Tied values share the average of their ranks, so heavily skewed fields like views are handled fairly \
+/// None in the same cases as pearson_correlation
+pub fn spearman_correlation(xs: &[f64], ys: &[f64]) -> Option<f64> {
+    pearson_correlation(&ranks(xs), &ranks(ys))
+}
+
+/// Finds the area under the ROC curve for scores of positive and negative examples \
+/// Uses the rank form of the Mann-Whitney U statistic, so tied scores count as half correct \
+/// **Output:** probability that a random positive outscores a random negative, 0.5 if either group is empty
+pub(crate) fn roc_auc(positive_scores: &[f64], negative_scores: &[f64]) -> f64 {
+    if positive_scores.is_empty() || negative_scores.is_empty() {
+        return 0.5
+    }
+    let all_scores: Vec<f64> = positive_scores.iter().chain(negative_scores).cloned().collect();
+    let score_ranks = ranks(&all_scores);
+    let positive_rank_sum: f64 = score_ranks[..positive_scores.len()].iter().sum();
+    let n_pos = positive_scores.len() as f64;
+    let n_neg = negative_scores.len() as f64;
+    (positive_rank_sum - n_pos * (n_pos + 1.0) / 2.0) / (n_pos * n_neg)
+}
+
+/// Converts a sample to its ranks (starting at 1), giving tied values their average rank
+fn ranks(values: &[f64]) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|a, b| values[*a].total_cmp(&values[*b]));
+    let mut ranked = vec![0.0; values.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start;
+        while end + 1 < order.len() && values[order[end + 1]] == values[order[start]] { // Extends over the run of tied values
+            end += 1;
+        }
+        let average_rank = (start + end) as f64 / 2.0 + 1.0;
+        for idx in &order[start..=end] {
+            ranked[*idx] = average_rank;
+        }
+        start = end + 1;
+    }
+    ranked
 }
 
 use plotters::prelude::*;
@@ -71,7 +142,7 @@ pub fn plot_viewership_distributions (distributions: Vec<Vec<(u32, f32)>>) {
 
         let mut sub_area_rows = (chunk.len() as f32).sqrt().floor() as usize;
         let sub_area_cols = (chunk.len() as f32).sqrt().ceil() as usize;
-        if sub_area_rows * sub_area_cols < chunk.len() as usize { //Ensures that there are enough slots for all clique graphs
+        if sub_area_rows * sub_area_cols < chunk.len() { //Ensures that there are enough slots for all clique graphs
             sub_area_rows += 1
         }
         
@@ -90,7 +161,7 @@ pub fn plot_viewership_distributions (distributions: Vec<Vec<(u32, f32)>>) {
             chart.configure_mesh() // Configure the chart labels and line thickness, referenced from lecture notes
                 .y_labels(10) // Attempts at configuring x_labels to start at 1 instead of 0 have resulted in values at index 0 not being graphed
                 .y_label_formatter(&|y| format!("{}%", (*y * 100.0) as u32)) // Reformat y values as %s
-                .light_line_style(&TRANSPARENT)
+                .light_line_style(TRANSPARENT)
                 .x_desc("Nodes in Clique")
                 .y_desc("% of Clique's Total Viewership")
                 .draw()
@@ -105,7 +176,7 @@ pub fn plot_viewership_distributions (distributions: Vec<Vec<(u32, f32)>>) {
 
             chart
                 .configure_series_labels()
-                .background_style(&WHITE.mix(0.8))
+                .background_style(WHITE.mix(0.8))
                 .draw()
                 .unwrap();
         }
@@ -120,20 +191,26 @@ use super::*;
     #[test]
     fn test_distributions () {
         let node1 = NodeStats {
+            id: 10,
             new_id: 1,
             views: 100,
+            days: 30,
             mature: true,
             partner: false,
         };
         let node2 = NodeStats {
+            id: 20,
             new_id: 2,
             views: 200,
+            days: 30,
             mature: true,
             partner: false,
         };
         let node3 = NodeStats {
+            id: 30,
             new_id: 3,
             views: 300,
+            days: 30,
             mature: true,
             partner: false,
         };  
@@ -151,4 +228,26 @@ use super::*;
 
     }
 
+    /// Tests the correlation helpers on perfectly linear, monotonic and tied samples
+    #[test]
+    fn test_correlations () {
+        let xs = vec![1.0, 2.0, 3.0, 4.0];
+        assert!((pearson_correlation(&xs, &[2.0, 4.0, 6.0, 8.0]).unwrap() - 1.0).abs() < 1e-9);
+        assert!((pearson_correlation(&xs, &[8.0, 6.0, 4.0, 2.0]).unwrap() + 1.0).abs() < 1e-9);
+        assert!((spearman_correlation(&xs, &[1.0, 10.0, 100.0, 1000.0]).unwrap() - 1.0).abs() < 1e-9, "Monotonic samples should have a rank correlation of 1");
+        assert_eq!(ranks(&[5.0, 1.0, 5.0, 3.0]), vec![3.5, 1.0, 3.5, 2.0]);
+        assert_eq!(pearson_correlation(&xs, &[1.0, 1.0, 1.0, 1.0]), Some(0.0));
+        assert_eq!(pearson_correlation(&xs, &[1.0, 2.0]), None, "Samples of different lengths should be rejected");
+        assert_eq!(spearman_correlation(&[1.0], &[2.0]), None, "A single pair has no correlation");
+    }
+
+    /// Tests the ROC AUC on perfect, inverted and tied scores
+    #[test]
+    fn test_roc_auc () {
+        assert_eq!(roc_auc(&[3.0, 4.0], &[1.0, 2.0]), 1.0);
+        assert_eq!(roc_auc(&[1.0, 2.0], &[3.0, 4.0]), 0.0);
+        assert_eq!(roc_auc(&[1.0, 1.0], &[1.0, 1.0]), 0.5, "Ties should count as half correct");
+        assert_eq!(roc_auc(&[2.0, 4.0], &[1.0, 3.0]), 0.75);
+    }
+
 }
\ No newline at end of file
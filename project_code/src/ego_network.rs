@@ -0,0 +1,221 @@
+//! Module for investigating a single streamer through their ego network: the streamer, their friends and the edges between them
+use std::collections::{BTreeSet, HashMap, HashSet};
+use plotters::prelude::*;
+use crate::bron_kerbosch;
+use crate::data_analysis::NodeStats;
+
+/// Finds a streamer's new_id from either their new_id or their original Twitch id \
+/// **Output:** the new_id, or None if no streamer in the target file matches
+pub fn resolve_node(stats: &HashMap<u32, NodeStats>, query: u32, is_twitch_id: bool) -> Option<u32> {
+    if is_twitch_id {
+        stats.values().find(|node| node.id == query).map(|node| node.new_id)
+    } else {
+        stats.get(&query).map(|node| node.new_id)
+    }
+}
+
+/// Finds the local clustering coefficient of a node, the share of pairs of its neighbors that are also friends \
+/// **Output:** value between 0 and 1, 0.0 for nodes with fewer than two neighbors
+pub fn clustering_coefficient(graph: &HashMap<u32, HashSet<u32>>, node: u32) -> f64 {
+    let Some(neighbors) = graph.get(&node) else { return 0.0 };
+    let degree = neighbors.len();
+    if degree < 2 {
+        return 0.0
+    }
+    let links: usize = neighbors.iter()
+        .map(|neighbor| graph[neighbor].intersection(neighbors).count())
+        .sum::<usize>() / 2; // Each link between neighbors is seen from both ends
+    links as f64 / (degree * (degree - 1) / 2) as f64
+}
+
+/// Finds the core number of every node, the largest k such that the node belongs to a subgraph
+/// where every node has at least k neighbors \
+/// ### Algorithm Logic
+/// Repeatedly remove the node with the lowest remaining degree \
+/// A node's core number is the largest removal degree seen up to and including its own removal \
+/// ### Output
+/// HashMap of node ids to core numbers
+pub fn core_numbers(graph: &HashMap<u32, HashSet<u32>>) -> HashMap<u32, usize> {
+    let mut degrees: HashMap<u32, usize> = graph.iter().map(|(node, neighbors)| (*node, neighbors.len())).collect();
+    let mut remaining: BTreeSet<(usize, u32)> = degrees.iter().map(|(node, degree)| (*degree, *node)).collect(); // Ordered by degree, then id
+    let mut cores = HashMap::new();
+    let mut current_core = 0;
+    while let Some((degree, node)) = remaining.pop_first() {
+        current_core = current_core.max(degree);
+        cores.insert(node, current_core);
+        for neighbor in &graph[&node] {
+            if cores.contains_key(neighbor) {
+                continue
+            }
+            let neighbor_degree = degrees.get_mut(neighbor).unwrap();
+            remaining.remove(&(*neighbor_degree, *neighbor));
+            *neighbor_degree -= 1;
+            remaining.insert((*neighbor_degree, *neighbor));
+        }
+    }
+    cores
+}
+
+/// Finds every maximal clique containing the node, by running the Bron-Kerbosch algorithm
+/// starting from r = {node} and p = the node's neighbors \
+/// **Output:** sorted cliques of at least min_value nodes, largest first
+pub fn cliques_containing(graph: &HashMap<u32, HashSet<u32>>, node: u32, min_value: u32) -> Vec<Vec<u32>> {
+    let r: HashSet<u32> = HashSet::from([node]);
+    let mut p: HashSet<u32> = graph.get(&node).cloned().unwrap_or_default();
+    let mut x: HashSet<u32> = HashSet::new();
+    let mut cliques: Vec<Vec<u32>> = Vec::new();
+    bron_kerbosch::run_bron_kerbosch(&r, &mut p, &mut x, graph, &mut cliques, min_value);
+    cliques.sort_by(|a, b| b.len().cmp(&a.len()).then(a.cmp(b)));
+    cliques
+}
+
+/// Builds the ego network of a node: the subgraph induced by the node and its neighbors \
+/// A node missing from the graph gets an ego network of just itself
+pub fn ego_network(graph: &HashMap<u32, HashSet<u32>>, node: u32) -> HashMap<u32, HashSet<u32>> {
+    let mut members: HashSet<u32> = graph.get(&node).cloned().unwrap_or_default();
+    members.insert(node);
+    members.iter()
+        .map(|member| (*member, graph.get(member).map(|neighbors| neighbors.intersection(&members).cloned().collect()).unwrap_or_default()))
+        .collect()
+}
+
+/// Summary of a single streamer within their region
+#[derive(Debug, Clone, PartialEq)]
+pub struct EgoReport {
+    pub node: NodeStats,
+    pub degree: usize,
+    pub clustering: f64,
+    pub core_number: usize,
+    pub cliques: Vec<Vec<u32>>,
+    pub views_rank: usize,
+    pub region_size: usize,
+    pub neighbor_mean_views: f64,
+    pub neighbor_median_views: f64,
+    pub neighbor_partner_share: f64,
+    pub neighbor_mature_share: f64,
+}
+
+/// Builds the EgoReport of a node \
+/// ### Inputs
+/// graph: HashMap graph such as the output of csv_to_hashmap \
+/// stats: NodeStats HashMap such as the output of load_target_stats, must contain the node \
+/// node: the streamer's new_id, see resolve_node \
+/// min_clique: the smallest clique size listed in the report \
+/// ### Output
+/// EgoReport, where views_rank is 1 for the most viewed streamer in the region and
+/// neighbor statistics only count neighbors found in stats
+pub fn ego_report(graph: &HashMap<u32, HashSet<u32>>, stats: &HashMap<u32, NodeStats>, node: u32, min_clique: u32) -> EgoReport {
+    let node_stats = stats[&node];
+    let empty = HashSet::new();
+    let neighbors = graph.get(&node).unwrap_or(&empty);
+    let neighbor_stats: Vec<&NodeStats> = neighbors.iter().filter_map(|neighbor| stats.get(neighbor)).collect();
+
+    let mut neighbor_views: Vec<u64> = neighbor_stats.iter().map(|neighbor| neighbor.views as u64).collect();
+    neighbor_views.sort();
+    let count = neighbor_views.len();
+    let share = |matches: usize| if count == 0 { 0.0 } else { matches as f64 / count as f64 };
+
+    EgoReport {
+        node: node_stats,
+        degree: neighbors.len(),
+        clustering: clustering_coefficient(graph, node),
+        core_number: core_numbers(graph).get(&node).cloned().unwrap_or(0),
+        cliques: cliques_containing(graph, node, min_clique),
+        views_rank: 1 + stats.values().filter(|other| other.views > node_stats.views).count(),
+        region_size: stats.len(),
+        neighbor_mean_views: if count == 0 { 0.0 } else { neighbor_views.iter().sum::<u64>() as f64 / count as f64 },
+        neighbor_median_views: match count {
+            0 => 0.0,
+            len if len % 2 == 0 => (neighbor_views[len / 2 - 1] + neighbor_views[len / 2]) as f64 / 2.0,
+            len => neighbor_views[len / 2] as f64,
+        },
+        neighbor_partner_share: share(neighbor_stats.iter().filter(|neighbor| neighbor.partner).count()),
+        neighbor_mature_share: share(neighbor_stats.iter().filter(|neighbor| neighbor.mature).count()),
+    }
+}
+
+/// Uses plotters to draw an ego network with the streamer in the center and their friends on a circle \
+/// **Input:** ego network from ego_network, the streamer's new_id, NodeStats HashMap and the output filename \
+/// Friends are placed around the circle in id order, partners are drawn in red and everyone else in blue \
+/// **Output:** drawing written to the given .png file in the project_code folder
+pub fn plot_ego_network(ego: &HashMap<u32, HashSet<u32>>, center: u32, stats: &HashMap<u32, NodeStats>, filename: &str) {
+    let mut friends: Vec<u32> = ego.keys().filter(|node| **node != center).cloned().collect();
+    friends.sort();
+    let mut positions: HashMap<u32, (f64, f64)> = HashMap::from([(center, (0.0, 0.0))]);
+    for (idx, friend) in friends.iter().enumerate() {
+        let angle = 2.0 * std::f64::consts::PI * idx as f64 / friends.len() as f64;
+        positions.insert(*friend, (angle.cos(), angle.sin()));
+    }
+
+    let root_area = BitMapBackend::new(filename, (1024, 1024))
+        .into_drawing_area();
+    root_area.fill(&WHITE).unwrap();
+
+    let mut chart = ChartBuilder::on(&root_area)
+        .caption(format!("Ego Network of Streamer {}", center), ("Arial", 20).into_font())
+        .margin(20)
+        .build_cartesian_2d(-1.2f64..1.2f64, -1.2f64..1.2f64).unwrap();
+
+    let mut edges: Vec<(u32, u32)> = ego.iter()
+        .flat_map(|(node, neighbors)| neighbors.iter().filter(move |neighbor| node < *neighbor).map(move |neighbor| (*node, *neighbor)))
+        .collect();
+    edges.sort();
+    chart.draw_series(edges.iter().map(|(a, b)| {
+        let color = if *a == center || *b == center { BLACK.mix(0.15) } else { BLACK.mix(0.4) }; // Friend-to-friend edges are darker
+        PathElement::new(vec![positions[a], positions[b]], color)
+    }))
+    .unwrap();
+
+    chart.draw_series(positions.iter().map(|(node, position)| {
+        let color = if stats.get(node).map(|node| node.partner).unwrap_or(false) { RED } else { BLUE };
+        let size = if *node == center { 10 } else { 5 };
+        Circle::new(*position, size, color.filled())
+    }))
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+use super::*;
+    use crate::random_graphs::graph_from_edges;
+
+    /// Tests clustering, core numbers, cliques and the ego network on a 4-clique with a tail, and the ego network of an isolated node
+    #[test]
+    fn test_ego_measures() {
+        let graph = graph_from_edges(&[(1, 2), (1, 3), (1, 4), (2, 3), (2, 4), (3, 4), (4, 5), (5, 6)]);
+
+        assert_eq!(clustering_coefficient(&graph, 1), 1.0);
+        assert_eq!(clustering_coefficient(&graph, 4), 3.0 / 6.0);
+        assert_eq!(clustering_coefficient(&graph, 6), 0.0);
+
+        let cores = core_numbers(&graph);
+        assert_eq!(cores[&1], 3);
+        assert_eq!(cores[&5], 1);
+
+        assert_eq!(cliques_containing(&graph, 4, 2), vec![vec![1, 2, 3, 4], vec![4, 5]]);
+        let ego = ego_network(&graph, 5);
+        assert_eq!(ego.len(), 3);
+        assert_eq!(ego[&4], HashSet::from([5]), "Edges leaving the ego network should be dropped");
+        assert_eq!(ego_network(&graph, 99), HashMap::from([(99, HashSet::new())]), "A node with no entry should be alone in its ego network");
+    }
+
+    /// Tests the report's rank and neighbor statistics, and looking streamers up by Twitch id
+    #[test]
+    fn test_ego_report() {
+        let graph = graph_from_edges(&[(1, 2), (1, 3)]);
+        let mut stats = HashMap::new();
+        for (new_id, views, partner) in [(1, 50, false), (2, 100, true), (3, 10, false)] {
+            stats.insert(new_id, NodeStats { id: new_id + 500, new_id, views, days: 10, mature: false, partner });
+        }
+
+        assert_eq!(resolve_node(&stats, 502, true), Some(2));
+        assert_eq!(resolve_node(&stats, 502, false), None);
+
+        let report = ego_report(&graph, &stats, 1, 2);
+        assert_eq!(report.degree, 2);
+        assert_eq!(report.views_rank, 2);
+        assert_eq!(report.neighbor_mean_views, 55.0);
+        assert_eq!(report.neighbor_partner_share, 0.5);
+        assert_eq!(report.cliques.len(), 2);
+    }
+}
@@ -0,0 +1,83 @@
+//! Module for the crate's error type, reporting which file and line failed while reading the data files
+use std::fmt;
+use std::num::ParseIntError;
+
+/// Error returned when reading or writing the edges, target and features files \
+/// Every variant carries the path of the file, and row-level variants carry the 1-based line number
+/// so batch jobs can point at the exact row that failed
+#[derive(Debug)]
+pub enum ReadError {
+    /// The file couldn't be opened, read or written
+    Io { path: String, source: std::io::Error },
+    /// The csv crate failed for a reason not covered by the other variants, line is None when unknown
+    Csv { path: String, line: Option<u64>, source: csv::Error },
+    /// The features JSON isn't valid, or doesn't map node ids to lists of feature indices
+    Json { path: String, line: usize, source: serde_json::Error },
+    /// A column that should hold a whole number (a node id, views or days) holds something else
+    ParseInt { path: String, line: u64, column: String, value: String, source: ParseIntError },
+    /// A node id column can't be read as the id type asked for, see read_edge_list_interned
+    InvalidId { path: String, line: u64, column: String, value: String },
+    /// A row ended before a required column
+    MissingColumn { path: String, line: u64, column: String },
+    /// A mature or partner column holds something other than True or False
+    UnknownBool { path: String, line: u64, column: String, value: String },
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadError::Io { path, source } => write!(f, "{}: {}", path, source),
+            ReadError::Csv { path, line: Some(line), source } => write!(f, "{}, line {}: {}", path, line, source),
+            ReadError::Csv { path, line: None, source } => write!(f, "{}: {}", path, source),
+            ReadError::Json { path, line, source } => write!(f, "{}, line {}: {}", path, line, source),
+            ReadError::ParseInt { path, line, column, value, source } =>
+                write!(f, "{}, line {}: column {} value {:?} is not a whole number ({})", path, line, column, value, source),
+            ReadError::InvalidId { path, line, column, value } =>
+                write!(f, "{}, line {}: column {} value {:?} is not a valid node id", path, line, column, value),
+            ReadError::MissingColumn { path, line, column } => write!(f, "{}, line {}: missing column {}", path, line, column),
+            ReadError::UnknownBool { path, line, column, value } =>
+                write!(f, "{}, line {}: column {} value {:?} is not True or False", path, line, column, value),
+        }
+    }
+}
+
+impl std::error::Error for ReadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReadError::Io { source, .. } => Some(source),
+            ReadError::Csv { source, .. } => Some(source),
+            ReadError::Json { source, .. } => Some(source),
+            ReadError::ParseInt { source, .. } => Some(source),
+            ReadError::InvalidId { .. } | ReadError::MissingColumn { .. } | ReadError::UnknownBool { .. } => None,
+        }
+    }
+}
+
+impl ReadError {
+    /// Wraps a csv::Error, pulling I/O failures out into the Io variant
+    pub fn from_csv(path: &str, err: csv::Error) -> ReadError {
+        let line = err.position().map(|position| position.line());
+        if err.is_io_error() {
+            if let csv::ErrorKind::Io(source) = err.into_kind() {
+                return ReadError::Io { path: path.to_string(), source }
+            }
+            unreachable!("is_io_error checked the kind")
+        }
+        ReadError::Csv { path: path.to_string(), line, source: err }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+use super::*;
+
+    /// Tests that csv I/O failures become the Io variant and messages name the file and line
+    #[test]
+    fn test_error_messages() {
+        let missing = csv::Reader::from_path("no_such_file.csv").unwrap_err();
+        assert!(matches!(ReadError::from_csv("no_such_file.csv", missing), ReadError::Io { .. }));
+
+        let err = ReadError::UnknownBool { path: "target.csv".to_string(), line: 3, column: "mature".to_string(), value: "yes".to_string() };
+        assert_eq!(err.to_string(), "target.csv, line 3: column mature value \"yes\" is not True or False");
+    }
+}
@@ -1,33 +1,327 @@
-//! Module for reading the csv files for both the graph edges and node statistics
+//! Module for reading the edge lists, csv and json files for the graph edges, node statistics and node features
 use std::collections::HashMap;
 use std::collections::HashSet;
-use std::error::Error;
+use std::hash::Hash;
+use std::io::Read;
+use std::str::FromStr;
+use std::time::Instant;
+use serde::de::DeserializeOwned;
 use crate::data_analysis::NodeStats;
+use crate::error::ReadError;
+use crate::interning::{InternedGraph, NodeInterner};
+use crate::progress::ProgressEvent;
+
+/// Edge rows between ProgressEvent::Parsing updates, and bytes between ProgressEvent::Reading updates
+const PROGRESS_ROWS: usize = 10_000;
+const PROGRESS_BYTES: usize = 1 << 20;
 
 /// Reads given csv file of edges (given path), returns HashMap of u32 keys and HashSet<u32> values \
-/// The HashMap functions as a **undirected** graph for further use
-pub fn csv_to_hashmap (path: &str) -> Result<HashMap<u32, HashSet<u32>>, Box<dyn Error>> {
-    let mut map: HashMap<u32, HashSet<u32>> = HashMap::new(); 
+/// The HashMap functions as a **undirected** graph for further use \
+/// The delimiter, header row and gzip compression are detected, see read_edge_list for other edge list formats \
+/// Errors name the file, line and column that failed, see ReadError
+pub fn csv_to_hashmap (path: &str) -> Result<HashMap<u32, HashSet<u32>>, ReadError> {
+    read_edge_list(path, &EdgeFormat::default())
+}
+
+/// Options for reading an edge list, where None means the option is detected from the file \
+/// ### Fields
+/// delimiter: byte between the two node ids, Some(b' ') splits on any run of spaces and tabs \
+/// has_headers: whether the first row that isn't a comment holds column names rather than node ids \
+/// comment: lines starting with this byte are skipped, such as the # lines at the top of SNAP files \
+/// gzip: whether the file is gzip compressed
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct EdgeFormat {
+    pub delimiter: Option<u8>,
+    pub has_headers: Option<bool>,
+    pub comment: Option<u8>,
+    pub gzip: Option<bool>,
+}
+
+impl Default for EdgeFormat {
+    /// Detects everything, skipping # comment lines
+    fn default() -> Self {
+        EdgeFormat { delimiter: None, has_headers: None, comment: Some(b'#'), gzip: None }
+    }
+}
+
+/// Reads an edge list in any of the formats used across the SNAP collection into an **undirected** HashMap graph \
+/// ### Inputs
+/// path: path to the edge list, or "-" to read from stdin \
+/// format: EdgeFormat options, EdgeFormat::default() detects everything \
+/// ### Detection
+/// gzip: the file starts with the gzip magic bytes 1f 8b \
+/// delimiter: a comma if the first data line has one, else any run of spaces and tabs \
+/// has_headers: the first data line has a column that isn't a whole number, such as from,to, while the line after it doesn't \
+/// ### Output
+/// HashMap graph, or a ReadError naming the line and column of the first row that fails \
+/// Columns after the first two, such as edge weights or timestamps, are ignored
+pub fn read_edge_list(path: &str, format: &EdgeFormat) -> Result<HashMap<u32, HashSet<u32>>, ReadError> {
+    read_edge_list_with_progress(path, format, &mut |_| ())
+}
+
+/// Reads an edge list like read_edge_list, calling on_progress as the file is read and as rows are added to the graph \
+/// Sends ProgressEvent::Reading every megabyte, then ProgressEvent::Parsing every 10,000 rows and once all rows are added
+pub fn read_edge_list_with_progress(path: &str, format: &EdgeFormat, on_progress: &mut dyn FnMut(&ProgressEvent)) -> Result<HashMap<u32, HashSet<u32>>, ReadError> {
+    let start = Instant::now();
+    let (headers, records) = read_edge_records(path, format, start, on_progress)?;
+    let mut map: HashMap<u32, HashSet<u32>> = HashMap::new();
+    for (idx, record) in records.iter().enumerate() {
+        report_rows(on_progress, idx, records.len(), start);
+        let start_node = parse_column(path, &headers, record, 0)?; // Parse both columns per row as u32
+        let end_node = parse_column(path, &headers, record, 1)?;
+        // Entry one way
+        map.entry(start_node) // Get HashSet for the start_node
+        .or_default() // If key doesn't exist, insert new HashSet
+        .insert(end_node); // Insert end_node into HashSet
+        // Reverse entry, makes graph undirected
+        map.entry(end_node)
+        .or_default()
+        .insert(start_node);
+    }
+    on_progress(&ProgressEvent::Parsing { rows: records.len(), total_rows: records.len(), elapsed: start.elapsed() });
+
+    Ok(map)
+}
+
+/// Sends a ProgressEvent::Parsing before every PROGRESS_ROWS-th row, skipping the first row
+fn report_rows(on_progress: &mut dyn FnMut(&ProgressEvent), rows: usize, total_rows: usize, start: Instant) {
+    if rows > 0 && rows.is_multiple_of(PROGRESS_ROWS) {
+        on_progress(&ProgressEvent::Parsing { rows, total_rows, elapsed: start.elapsed() });
+    }
+}
+
+/// Reads an edge list keyed by any id type, such as String usernames or u64 ids, interning each id as a dense u32 \
+/// Reads the same formats as read_edge_list, though a header can only be detected when the ids are whole numbers,
+/// so set has_headers for files of names \
+/// **Output:** InternedGraph, holding the graph of internal ids and the NodeInterner for translating results back \
+/// Ids are interned in the order they first appear in the file, and ids that can't be parsed as T return ReadError::InvalidId
+pub fn read_edge_list_interned<T>(path: &str, format: &EdgeFormat) -> Result<InternedGraph<T>, ReadError>
+where
+    T: FromStr + Eq + Hash + Clone,
+{
+    read_edge_list_interned_with_progress(path, format, &mut |_| ())
+}
+
+/// Reads an edge list like read_edge_list_interned, calling on_progress like read_edge_list_with_progress
+pub fn read_edge_list_interned_with_progress<T>(path: &str, format: &EdgeFormat, on_progress: &mut dyn FnMut(&ProgressEvent)) -> Result<InternedGraph<T>, ReadError>
+where
+    T: FromStr + Eq + Hash + Clone,
+{
+    let start = Instant::now();
+    let (headers, records) = read_edge_records(path, format, start, on_progress)?;
+    let mut interner: NodeInterner<T> = NodeInterner::new();
+    let mut map: HashMap<u32, HashSet<u32>> = HashMap::new();
+    for (idx, record) in records.iter().enumerate() {
+        report_rows(on_progress, idx, records.len(), start);
+        let mut ends = [0u32; 2];
+        for (column, end) in ends.iter_mut().enumerate() {
+            let line = record.position().map(|position| position.line()).unwrap_or(0);
+            let value = record.get(column).ok_or_else(|| ReadError::MissingColumn {
+                path: path.to_string(), line, column: column_name(&headers, column)
+            })?;
+            let id = value.parse::<T>().map_err(|_| ReadError::InvalidId {
+                path: path.to_string(), line, column: column_name(&headers, column), value: value.to_string()
+            })?;
+            *end = interner.intern(id);
+        }
+        map.entry(ends[0]).or_default().insert(ends[1]);
+        map.entry(ends[1]).or_default().insert(ends[0]);
+    }
+    on_progress(&ProgressEvent::Parsing { rows: records.len(), total_rows: records.len(), elapsed: start.elapsed() });
+    Ok(InternedGraph { graph: map, interner })
+}
+
+/// Reads, decompresses and splits an edge list into rows, see read_edge_list for the detection rules \
+/// Sends a ProgressEvent::Reading after each megabyte read, timed from start \
+/// **Output:** the header row (empty if there is none) and every other row, each keeping its line number in the file
+fn read_edge_records(
+    path: &str,
+    format: &EdgeFormat,
+    start: Instant,
+    on_progress: &mut dyn FnMut(&ProgressEvent),
+) -> Result<(csv::StringRecord, Vec<csv::StringRecord>), ReadError> {
+    let io_error = |source| ReadError::Io { path: path.to_string(), source };
+    let (mut reader, total_bytes): (Box<dyn Read>, Option<u64>) = if path == "-" {
+        (Box::new(std::io::stdin().lock()), None)
+    } else {
+        let file = std::fs::File::open(path).map_err(io_error)?;
+        let size = file.metadata().map(|metadata| metadata.len()).ok();
+        (Box::new(file), size)
+    };
+    let mut bytes: Vec<u8> = Vec::new();
+    let mut chunk = vec![0u8; PROGRESS_BYTES];
+    loop {
+        let read = match reader.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(io_error(err)),
+        };
+        bytes.extend_from_slice(&chunk[..read]);
+        on_progress(&ProgressEvent::Reading { bytes: bytes.len() as u64, total_bytes, elapsed: start.elapsed() });
+    }
+    if format.gzip.unwrap_or(bytes.starts_with(&[0x1f, 0x8b])) {
+        let mut decoded = Vec::new();
+        flate2::read::MultiGzDecoder::new(&bytes[..]).read_to_end(&mut decoded).map_err(io_error)?;
+        bytes = decoded;
+    }
+    let text = String::from_utf8_lossy(&bytes);
+
+    // Line numbers are kept so errors point at the right row even after skipping comments and blank lines
+    let comment = format.comment.map(|byte| byte as char);
+    let lines: Vec<(u64, &str)> = text.lines().enumerate()
+        .map(|(idx, line)| (idx as u64 + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !comment.is_some_and(|comment| line.starts_with(comment)))
+        .collect();
+
+    let first_line = lines.first().map(|(_, line)| *line).unwrap_or("");
+    let delimiter = format.delimiter.map(|byte| byte as char).unwrap_or_else(|| {
+        if first_line.contains(',') { ',' } else { ' ' }
+    });
+    let split = |line: &'_ str| -> Vec<String> {
+        match delimiter {
+            ' ' => line.split_whitespace().map(|value| value.to_string()).collect(),
+            delimiter => line.split(delimiter).map(|value| value.trim().to_string()).collect(),
+        }
+    };
+    let has_headers = format.has_headers.unwrap_or_else(|| {
+        let numeric = |line: &str| split(line).iter().take(2).all(|value| value.parse::<u64>().is_ok());
+        !numeric(first_line) && lines.get(1).is_none_or(|(_, line)| numeric(line))
+    });
+    let headers = csv::StringRecord::from(if has_headers { split(first_line) } else { Vec::new() });
+
+    let records = lines.iter().skip(has_headers as usize).map(|(line, text)| {
+        let mut record = csv::StringRecord::from(split(text));
+        let mut position = csv::Position::new();
+        position.set_line(*line);
+        record.set_position(Some(position));
+        record
+    }).collect();
+    Ok((headers, records))
+}
+
+/// Name of a column for error messages, the header if there is one or else the column index
+fn column_name(headers: &csv::StringRecord, column: usize) -> String {
+    headers.get(column).map(|name| name.to_string()).unwrap_or_else(|| column.to_string())
+}
+
+/// Parses one column of a row as a u32, reporting the file, line and column if it is missing or not a whole number
+fn parse_column(path: &str, headers: &csv::StringRecord, record: &csv::StringRecord, column: usize) -> Result<u32, ReadError> {
+    let line = record.position().map(|position| position.line()).unwrap_or(0);
+    let value = record.get(column).ok_or_else(|| ReadError::MissingColumn {
+        path: path.to_string(), line, column: column_name(headers, column)
+    })?;
+    value.parse::<u32>().map_err(|source| ReadError::ParseInt {
+        path: path.to_string(), line, column: column_name(headers, column), value: value.to_string(), source
+    })
+}
+
+/// Converts an error from reading a csv row, turning rows shorter than the header into MissingColumn
+fn row_error(path: &str, headers: &csv::StringRecord, err: csv::Error) -> ReadError {
+    if let csv::ErrorKind::UnequalLengths { pos: Some(pos), expected_len, len } = err.kind()
+        && len < expected_len {
+        return ReadError::MissingColumn { path: path.to_string(), line: pos.line(), column: column_name(headers, *len as usize) }
+    }
+    ReadError::from_csv(path, err)
+}
+
+/// Reads every row of a target.csv file as NodeStats, keeping each row's error rather than stopping at the first \
+/// Deserialization errors are converted to ReadError variants naming the failing column: ParseInt for
+/// id, days, views and new_id, UnknownBool for mature and partner, and MissingColumn for short rows
+fn read_target_rows<Id: DeserializeOwned>(path: &str) -> Result<Vec<Result<NodeStats<Id>, ReadError>>, ReadError> {
     let mut rdr = csv::ReaderBuilder::new()
-    .has_headers(true) // edges file does not have a header
-    .from_path(path)?; // Build reader from path given as an argument
+        .has_headers(true)
+        .from_path(path).map_err(|err| ReadError::from_csv(path, err))?;
+    let headers = rdr.headers().map_err(|err| ReadError::from_csv(path, err))?.clone();
+    let mut rows = Vec::new();
     for result in rdr.records() {
-     let record = result?;
-     let start_node = record.get(0).unwrap().parse::<u32>()?; // Parse both columns per row as u32
-     let end_node = record.get(1).unwrap().parse::<u32>()?;
-     // Entry one way
-     map.entry(start_node) // Get HashSet for the start_node
-     .or_insert_with(|| HashSet::new()) // If key doesn't exist, insert new HashSet
-     .insert(end_node); // Insert end_node into HashSet
-     // Reverse entry, makes graph undirected
-     map.entry(end_node)
-     .or_insert_with(|| HashSet::new())
-     .insert(start_node);
- 
-    }
- 
-    return Ok(map)
- }
+        let row = result
+            .map_err(|err| row_error(path, &headers, err))
+            .and_then(|record| record.deserialize::<NodeStats<Id>>(Some(&headers)).map_err(|err| target_row_error(path, &headers, &record, err)));
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+/// Converts a NodeStats deserialization error into the ReadError variant for the failing column
+fn target_row_error(path: &str, headers: &csv::StringRecord, record: &csv::StringRecord, err: csv::Error) -> ReadError {
+    let line = record.position().map(|position| position.line()).unwrap_or(0);
+    if let csv::ErrorKind::Deserialize { err: de_err, .. } = err.kind() {
+        let field = de_err.field().map(|field| field as usize);
+        let column = field.map(|field| column_name(headers, field)).unwrap_or_else(|| "unknown".to_string());
+        let value = field.and_then(|field| record.get(field)).unwrap_or("").to_string();
+        match de_err.kind() {
+            csv::DeserializeErrorKind::ParseInt(source) =>
+                return ReadError::ParseInt { path: path.to_string(), line, column, value, source: source.clone() },
+            csv::DeserializeErrorKind::UnexpectedEndOfRow =>
+                return ReadError::MissingColumn { path: path.to_string(), line, column },
+            csv::DeserializeErrorKind::Message(_) => { // Raised by deserialize_bool, which doesn't say which column failed
+                let bool_columns = headers.iter().enumerate().filter(|(_, name)| *name == "mature" || *name == "partner");
+                for (idx, name) in bool_columns {
+                    let value = record.get(idx).unwrap_or("");
+                    if value != "True" && value != "False" {
+                        return ReadError::UnknownBool { path: path.to_string(), line, column: name.to_string(), value: value.to_string() }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    ReadError::from_csv(path, err)
+}
+
+/// Writes a HashMap graph to the given path in the same from,to format as the edges files \
+/// Each undirected edge is written once, as (smaller id, larger id), sorted so the output is consistent \
+/// The written file can be read back with csv_to_hashmap, though nodes with no edges are lost
+pub fn write_edge_csv(graph: &HashMap<u32, HashSet<u32>>, path: &str) -> Result<(), ReadError> {
+    let mut edges: Vec<(u32, u32)> = graph.iter()
+        .flat_map(|(node, neighbors)| neighbors.iter().filter(move |neighbor| node < *neighbor).map(move |neighbor| (*node, *neighbor)))
+        .collect();
+    edges.sort();
+    let mut wtr = csv::Writer::from_path(path).map_err(|err| ReadError::from_csv(path, err))?;
+    wtr.write_record(["from", "to"]).map_err(|err| ReadError::from_csv(path, err))?;
+    for (start_node, end_node) in edges {
+        wtr.write_record([start_node.to_string(), end_node.to_string()]).map_err(|err| ReadError::from_csv(path, err))?;
+    }
+    wtr.flush().map_err(|source| ReadError::Io { path: path.to_string(), source })?;
+    Ok(())
+}
+
+/// Reads the given target.csv file into a HashMap of new_id keys and NodeStats values \
+/// Allows NodeStats lookups for any node, rather than only nodes within cliques \
+/// Stops at the first row that fails, see read_target_rows for the errors returned
+pub fn load_target_stats(path: &str) -> Result<HashMap<u32, NodeStats>, ReadError> {
+    load_target_stats_as::<u32>(path)
+}
+
+/// Reads a target.csv file like load_target_stats, with the id column read as any type, such as String usernames
+pub fn load_target_stats_as<Id: DeserializeOwned>(path: &str) -> Result<HashMap<u32, NodeStats<Id>>, ReadError> {
+    let mut stats: HashMap<u32, NodeStats<Id>> = HashMap::new();
+    for row in read_target_rows(path)? {
+        let record = row?;
+        stats.insert(record.new_id, record);
+    }
+    Ok(stats)
+}
+
+/// Reads the given features.json file into a HashMap of new_id keys and feature index values \
+/// The JSON maps each node id (as a string) to the list of features the node has, such as {"0": [12, 250, 3001]} \
+/// Features listed more than once for a node are kept once, and each list is sorted
+pub fn load_features(path: &str) -> Result<HashMap<u32, Vec<u32>>, ReadError> {
+    let file = std::fs::File::open(path).map_err(|source| ReadError::Io { path: path.to_string(), source })?;
+    let raw: HashMap<String, Vec<u32>> = serde_json::from_reader(std::io::BufReader::new(file))
+        .map_err(|source| ReadError::Json { path: path.to_string(), line: source.line(), source })?;
+    let mut features: HashMap<u32, Vec<u32>> = HashMap::new();
+    for (node_id, mut node_features) in raw {
+        node_features.sort();
+        node_features.dedup(); // Some nodes list the same feature twice
+        let parsed_id = node_id.parse::<u32>().map_err(|source| ReadError::ParseInt {
+            path: path.to_string(), line: 1, column: "node id".to_string(), value: node_id.clone(), source // The features files are a single line
+        })?;
+        features.insert(parsed_id, node_features);
+    }
+    Ok(features)
+}
 
 /// Once cliques are found using Bron_Kerbosch on u32 values (computationally faster),
 /// remake the cliques using NodeStats structs for further data analysis \
@@ -44,17 +338,14 @@ pub fn csv_to_hashmap (path: &str) -> Result<HashMap<u32, HashSet<u32>>, Box<dyn
 /// **Possible optimization (ran out of time):** collecting ids from target csv as a Vec<u32> with a reader, 
 /// then using that vec to find the row index for each clique node, and calling reader directly to that row. \
 /// Requires loading the bit offset to have the reader find specific rows, too technical at the moment
-pub fn load_target_file_replace_u32_cliques(path: &str, cliques: Vec<Vec<u32>>) -> Result<Vec<Vec<NodeStats>>, csv::Error> {
+pub fn load_target_file_replace_u32_cliques(path: &str, cliques: Vec<Vec<u32>>) -> Result<Vec<Vec<NodeStats>>, ReadError> {
     let mut loaded_file: Vec<NodeStats> = Vec::new();
-    let mut index_rdr = csv::ReaderBuilder::new()
-        .has_headers(true)
-        .from_path(path)?;
-    for result in index_rdr.deserialize::<NodeStats>() {
+    for result in read_target_rows(path)? {
         match result {
             Ok(record) => {
                 loaded_file.push(record)
             }
-            Err(err) => eprintln!("Error deserializing csv: {}", err)
+            Err(err) => eprintln!("Error deserializing csv: {}", err) // Skips the failing row, the error names its line
         }
     }
     // Continue rework here
@@ -63,7 +354,7 @@ pub fn load_target_file_replace_u32_cliques(path: &str, cliques: Vec<Vec<u32>>)
         let mut node_clique: Vec<NodeStats> = Vec::new();
         for node_id in clique { 
             if let Some(matching_node) = loaded_file.iter().find(|node| node.new_id == node_id) {
-                node_clique.push(matching_node.clone())
+                node_clique.push(*matching_node)
             }            
         }
         node_cliques.push(node_clique) // Pushes a Vec<NodeStats> onto another vector
@@ -103,6 +394,62 @@ mod tests {
 
         assert_eq!(graph, verified_graph);
     }  
+    /// Test that SNAP style edge lists, tab separated with # comments and no header, read with detected options and with gzip
+    #[test]
+    fn test_edge_formats () {
+        let snap_text = "# Directed graph: example.txt\n# FromNodeId\tToNodeId\n1\t2\n1\t3\n\n2   3\n";
+        let mut temp_edge_file = NamedTempFile::new().unwrap();
+        write!(temp_edge_file, "{}", snap_text).unwrap();
+        let tabs = EdgeFormat { delimiter: Some(b'\t'), has_headers: Some(false), ..EdgeFormat::default() };
+        let graph = read_edge_list(temp_edge_file.path().to_str().unwrap(), &tabs);
+        assert!(matches!(graph, Err(ReadError::ParseInt { line: 6, .. })), "Spaces shouldn't split columns when tabs are given");
+
+        let graph = read_edge_list(temp_edge_file.path().to_str().unwrap(), &EdgeFormat::default()).unwrap();
+        assert_eq!(graph[&3], HashSet::from([1, 2]));
+
+        let mut temp_gzip_file = NamedTempFile::new().unwrap();
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(snap_text.replace("2   3", "2\t3").as_bytes()).unwrap();
+        temp_gzip_file.write_all(&encoder.finish().unwrap()).unwrap();
+        assert_eq!(csv_to_hashmap(temp_gzip_file.path().to_str().unwrap()).unwrap(), graph);
+    }
+    /// Test reading edge lists of usernames and ids too large for u32 through the interner, and target files keyed by username
+    #[test]
+    fn test_interned_reading () {
+        let mut temp_name_file = NamedTempFile::new().unwrap();
+        write!(temp_name_file, "# usernames\nalice bob\nbob carol\nalice carol\ncarol dan\n").unwrap();
+        let names: InternedGraph<String> = read_edge_list_interned(temp_name_file.path().to_str().unwrap(), &EdgeFormat::default()).unwrap();
+        assert_eq!(names.interner.len(), 4, "A line of names followed by more names isn't a header");
+        let carol = names.interner.get(&"carol".to_string()).unwrap();
+        assert_eq!(names.graph[&carol].len(), 3);
+
+        let mut temp_large_file = NamedTempFile::new().unwrap();
+        write!(temp_large_file, "from,to\n10000000000,1\n1,x\n").unwrap();
+        let path = temp_large_file.path().to_str().unwrap();
+        assert!(matches!(csv_to_hashmap(path), Err(ReadError::ParseInt { line: 2, .. })));
+        assert!(matches!(read_edge_list_interned::<u64>(path, &EdgeFormat::default()), Err(ReadError::InvalidId { line: 3, .. })));
+
+        let mut temp_target_file = NamedTempFile::new().unwrap();
+        writeln!(temp_target_file, "id,days,mature,views,partner,new_id").unwrap();
+        writeln!(temp_target_file, "alice,10,True,100,False,0").unwrap();
+        let stats = load_target_stats_as::<String>(temp_target_file.path().to_str().unwrap()).unwrap();
+        assert_eq!(stats[&0].id, "alice");
+    }
+    /// Test that a written edge file reads back as the same graph
+    #[test]
+    fn test_edge_writing () {
+        let mut graph: HashMap<u32, HashSet<u32>> = HashMap::new();
+        graph.entry(1).or_default().extend([2, 3]);
+        graph.entry(2).or_default().extend([1]);
+        graph.entry(3).or_default().extend([1]);
+
+        let temp_edge_file = NamedTempFile::new().unwrap();
+        let path = temp_edge_file.path().to_str().unwrap();
+        write_edge_csv(&graph, path).unwrap();
+
+        assert_eq!(std::fs::read_to_string(path).unwrap(), "from,to\n1,2\n1,3\n");
+        assert_eq!(csv_to_hashmap(path).unwrap(), graph);
+    }
     /// Test reading of the target files using a temporary file 
     #[test]
     fn test_target_reading () {
@@ -120,15 +467,19 @@ mod tests {
             test_u32_cliques).unwrap();
         
         let node1= NodeStats {
+            id: 1,
             new_id: 6,
             views: 4,
+            days: 2,
             mature: true,
             partner: true
         };
 
         let node2 = NodeStats {
+            id: 7,
             new_id: 12,
             views: 10,
+            days: 8,
             mature: false,
             partner: false
         };
@@ -137,4 +488,47 @@ mod tests {
 
         assert_eq!(test_vec, verified_vec);
     }
+    /// Test reading of the features files using a temporary file
+    #[test]
+    fn test_features_reading () {
+        let mut temp_features_file = NamedTempFile::new().unwrap();
+        writeln!(temp_features_file, r#"{{"0": [5, 2, 5], "1": []}}"#).unwrap();
+
+        let path = temp_features_file.path().to_str().unwrap();
+
+        let features = load_features(path).unwrap();
+        assert_eq!(features[&0], vec![2, 5], "Features should be sorted and deduplicated");
+        assert_eq!(features[&1], Vec::<u32>::new());
+    }
+    /// Test that bad rows report the matching error variant with the right line and column
+    #[test]
+    fn test_read_errors () {
+        let mut temp_edge_file = NamedTempFile::new().unwrap();
+        writeln!(temp_edge_file, "from,to").unwrap();
+        writeln!(temp_edge_file, "1,2").unwrap();
+        writeln!(temp_edge_file, "3,x").unwrap();
+        let path = temp_edge_file.path().to_str().unwrap();
+        match csv_to_hashmap(path) {
+            Err(ReadError::ParseInt { line, column, value, .. }) => assert_eq!((line, column.as_str(), value.as_str()), (3, "to", "x")),
+            other => panic!("Expected ParseInt, got {:?}", other),
+        }
+
+        let mut temp_short_file = NamedTempFile::new().unwrap();
+        writeln!(temp_short_file, "from,to").unwrap();
+        writeln!(temp_short_file, "1").unwrap();
+        let path = temp_short_file.path().to_str().unwrap();
+        assert!(matches!(csv_to_hashmap(path), Err(ReadError::MissingColumn { line: 2, .. })));
+
+        let mut temp_target_file = NamedTempFile::new().unwrap();
+        writeln!(temp_target_file, "id,days,mature,views,partner,new_id").unwrap();
+        writeln!(temp_target_file, "1,2,True,4,True,6").unwrap();
+        writeln!(temp_target_file, "7,8,false,10,False,12").unwrap();
+        let path = temp_target_file.path().to_str().unwrap();
+        match load_target_stats(path) {
+            Err(ReadError::UnknownBool { line, column, value, .. }) => assert_eq!((line, column.as_str(), value.as_str()), (3, "mature", "false")),
+            other => panic!("Expected UnknownBool, got {:?}", other),
+        }
+
+        assert!(matches!(load_features("no_such_file.json"), Err(ReadError::Io { .. })));
+    }
 }
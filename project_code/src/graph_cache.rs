@@ -0,0 +1,238 @@
+//! Module for caching parsed regions in a compact binary file, so later runs skip parsing the csv files
+use std::collections::{HashMap, HashSet};
+use std::time::UNIX_EPOCH;
+use crate::data_analysis::NodeStats;
+use crate::error::ReadError;
+use crate::file_reading;
+use crate::progress::ProgressEvent;
+
+const MAGIC: &[u8; 8] = b"TWGCACHE";
+const VERSION: u32 = 1; // Raise whenever the layout below changes, so older caches are rebuilt
+
+/// A region's graph and target table, and whether they came from the cache rather than the csv files
+#[derive(Debug, Clone, PartialEq)]
+pub struct CachedRegion {
+    pub graph: HashMap<u32, HashSet<u32>>,
+    pub stats: HashMap<u32, NodeStats>,
+    pub from_cache: bool,
+}
+
+/// Identifies one version of a source file by its path, size and modification time
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SourceKey {
+    path: String,
+    size: u64,
+    modified: u64, // Nanoseconds since the Unix epoch
+}
+
+/// Reads the path, size and modification time of a source file
+fn source_key(path: &str) -> Result<SourceKey, ReadError> {
+    let metadata = std::fs::metadata(path).map_err(|source| ReadError::Io { path: path.to_string(), source })?;
+    let modified = metadata.modified().ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_nanos() as u64)
+        .unwrap_or(0);
+    Ok(SourceKey { path: path.to_string(), size: metadata.len(), modified })
+}
+
+/// Path of the cache file for an edges file, inside cache_dir \
+/// Every character of the edges path that isn't a letter or digit becomes _, such as graph_cache/___twitch_data_DE_musae_DE_edges_csv.bin
+pub fn cache_path(cache_dir: &str, edge_path: &str) -> String {
+    let name: String = edge_path.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect();
+    format!("{}/{}.bin", cache_dir, name)
+}
+
+/// True when cache_dir holds a cache for this region written from the current versions of both csv files \
+/// Only the header is checked, so a cache that is current but corrupted further on still counts as current
+pub fn is_current(edge_path: &str, target_path: &str, cache_dir: &str) -> bool {
+    let (Ok(edge_key), Ok(target_key)) = (source_key(edge_path), source_key(target_path)) else {
+        return false
+    };
+    std::fs::read(cache_path(cache_dir, edge_path))
+        .is_ok_and(|bytes| read_header(&mut Cursor { bytes: &bytes, pos: 0 }, &[edge_key, target_key]).is_some())
+}
+
+/// Loads a region's graph and target table, from the cache when it is current or else from the csv files \
+/// ### Inputs
+/// edge_path, target_path: the region's edges and target csv files \
+/// cache_dir: folder holding the cache files, created if missing \
+/// ### Cache Logic
+/// The cache stores the path, size and modification time of both csv files \
+/// If either file has changed, or the cache is missing, unreadable or from an older layout,
+/// both files are parsed with csv_to_hashmap and load_target_stats and the cache is rewritten \
+/// ### Output
+/// CachedRegion, or a ReadError if the csv files can't be read or the cache can't be written
+pub fn load_region_cached(edge_path: &str, target_path: &str, cache_dir: &str) -> Result<CachedRegion, ReadError> {
+    load_region_cached_with_progress(edge_path, target_path, cache_dir, &mut |_| ())
+}
+
+/// Loads a region like load_region_cached, calling on_progress while the edges csv is parsed \
+/// Nothing is sent when the region comes from the cache, see file_reading::read_edge_list_with_progress for the events
+pub fn load_region_cached_with_progress(
+    edge_path: &str,
+    target_path: &str,
+    cache_dir: &str,
+    on_progress: &mut dyn FnMut(&ProgressEvent),
+) -> Result<CachedRegion, ReadError> {
+    let keys = [source_key(edge_path)?, source_key(target_path)?];
+    let path = cache_path(cache_dir, edge_path);
+    if let Ok(bytes) = std::fs::read(&path)
+        && let Some(region) = decode(&bytes, &keys) {
+        return Ok(region)
+    }
+
+    let graph = file_reading::read_edge_list_with_progress(edge_path, &file_reading::EdgeFormat::default(), on_progress)?;
+    let stats = file_reading::load_target_stats(target_path)?;
+    std::fs::create_dir_all(cache_dir).map_err(|source| ReadError::Io { path: cache_dir.to_string(), source })?;
+    let temp_path = format!("{}.tmp", path); // Written then renamed, so an interrupted write never leaves a broken cache
+    std::fs::write(&temp_path, encode(&keys, &graph, &stats))
+        .and_then(|_| std::fs::rename(&temp_path, &path))
+        .map_err(|source| ReadError::Io { path: path.clone(), source })?;
+    Ok(CachedRegion { graph, stats, from_cache: false })
+}
+
+/// Encodes the source keys, graph and target table in little-endian binary \
+/// ### Layout
+/// MAGIC, VERSION, then for each source: path length, path bytes, size, modification time \
+/// Node count, then for each node in id order: id, degree, sorted neighbor ids \
+/// Row count, then for each row in new_id order: id, new_id, views, days, mature, partner
+fn encode(keys: &[SourceKey], graph: &HashMap<u32, HashSet<u32>>, stats: &HashMap<u32, NodeStats>) -> Vec<u8> {
+    let mut bytes: Vec<u8> = MAGIC.to_vec();
+    bytes.extend(VERSION.to_le_bytes());
+    for key in keys {
+        bytes.extend((key.path.len() as u32).to_le_bytes());
+        bytes.extend(key.path.as_bytes());
+        bytes.extend(key.size.to_le_bytes());
+        bytes.extend(key.modified.to_le_bytes());
+    }
+
+    let mut nodes: Vec<&u32> = graph.keys().collect();
+    nodes.sort();
+    bytes.extend((nodes.len() as u32).to_le_bytes());
+    for node in nodes {
+        let mut neighbors: Vec<&u32> = graph[node].iter().collect();
+        neighbors.sort();
+        bytes.extend(node.to_le_bytes());
+        bytes.extend((neighbors.len() as u32).to_le_bytes());
+        for neighbor in neighbors {
+            bytes.extend(neighbor.to_le_bytes());
+        }
+    }
+
+    let mut rows: Vec<&NodeStats> = stats.values().collect();
+    rows.sort_by_key(|row| row.new_id);
+    bytes.extend((rows.len() as u32).to_le_bytes());
+    for row in rows {
+        for value in [row.id, row.new_id, row.views, row.days] {
+            bytes.extend(value.to_le_bytes());
+        }
+        bytes.push(row.mature as u8);
+        bytes.push(row.partner as u8);
+    }
+    bytes
+}
+
+/// Decodes a cache written by encode \
+/// **Output:** CachedRegion with from_cache set, or None if the cache is truncated, from another layout or its keys don't match
+fn decode(bytes: &[u8], keys: &[SourceKey]) -> Option<CachedRegion> {
+    let mut cursor = Cursor { bytes, pos: 0 };
+    read_header(&mut cursor, keys)?;
+
+    let mut graph: HashMap<u32, HashSet<u32>> = HashMap::new();
+    for _ in 0..cursor.u32()? {
+        let node = cursor.u32()?;
+        let degree = cursor.u32()?;
+        let neighbors = (0..degree).map(|_| cursor.u32()).collect::<Option<HashSet<u32>>>()?;
+        graph.insert(node, neighbors);
+    }
+
+    let mut stats: HashMap<u32, NodeStats> = HashMap::new();
+    for _ in 0..cursor.u32()? {
+        let (id, new_id, views, days) = (cursor.u32()?, cursor.u32()?, cursor.u32()?, cursor.u32()?);
+        let (mature, partner) = (cursor.take(1)?[0] == 1, cursor.take(1)?[0] == 1);
+        stats.insert(new_id, NodeStats { id, new_id, views, days, mature, partner });
+    }
+    if cursor.pos != bytes.len() { // Trailing bytes mean the file wasn't written by this layout
+        return None
+    }
+    Some(CachedRegion { graph, stats, from_cache: true })
+}
+
+/// Reads MAGIC, VERSION and the source keys, returning None if any of them doesn't match
+fn read_header(cursor: &mut Cursor, keys: &[SourceKey]) -> Option<()> {
+    if cursor.take(MAGIC.len())? != MAGIC || cursor.u32()? != VERSION {
+        return None
+    }
+    for key in keys {
+        let path_len = cursor.u32()? as usize;
+        let path = String::from_utf8(cursor.take(path_len)?.to_vec()).ok()?;
+        let cached = SourceKey { path, size: cursor.u64()?, modified: cursor.u64()? };
+        if cached != *key {
+            return None
+        }
+    }
+    Some(())
+}
+
+/// Reads values from the front of a byte slice, returning None once the bytes run out \
+/// Also used by the checkpoint module, which writes the same little-endian layout
+pub(crate) struct Cursor<'a> {
+    pub(crate) bytes: &'a [u8],
+    pub(crate) pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    pub(crate) fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let slice = self.bytes.get(self.pos..self.pos.checked_add(len)?)?;
+        self.pos += len;
+        Some(slice)
+    }
+
+    pub(crate) fn u32(&mut self) -> Option<u32> {
+        self.take(4).map(|slice| u32::from_le_bytes(slice.try_into().unwrap()))
+    }
+
+    pub(crate) fn u64(&mut self) -> Option<u64> {
+        self.take(8).map(|slice| u64::from_le_bytes(slice.try_into().unwrap()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+use super::*;
+    use std::io::Write;
+
+    /// Tests that the second load comes from the cache, and that changed or corrupted files cause a rebuild
+    #[test]
+    fn test_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let edge_path = dir.path().join("edges.csv").to_str().unwrap().to_string();
+        let target_path = dir.path().join("target.csv").to_str().unwrap().to_string();
+        let cache_dir = dir.path().join("cache").to_str().unwrap().to_string();
+        std::fs::write(&edge_path, "from,to\n1,2\n2,3\n").unwrap();
+        std::fs::write(&target_path, "id,days,mature,views,partner,new_id\n501,10,True,100,False,1\n502,20,False,5,True,2\n").unwrap();
+
+        assert!(!is_current(&edge_path, &target_path, &cache_dir));
+        let first = load_region_cached(&edge_path, &target_path, &cache_dir).unwrap();
+        assert!(!first.from_cache);
+        assert!(is_current(&edge_path, &target_path, &cache_dir));
+        let second = load_region_cached(&edge_path, &target_path, &cache_dir).unwrap();
+        assert!(second.from_cache);
+        assert_eq!((&second.graph, &second.stats), (&first.graph, &first.stats));
+        assert!(second.stats[&2].partner);
+
+        let mut edge_file = std::fs::OpenOptions::new().append(true).open(&edge_path).unwrap();
+        writeln!(edge_file, "3,4").unwrap();
+        assert!(!is_current(&edge_path, &target_path, &cache_dir));
+        let changed = load_region_cached(&edge_path, &target_path, &cache_dir).unwrap();
+        assert!(!changed.from_cache, "A changed edges file should rebuild the cache");
+        assert_eq!(changed.graph[&4], HashSet::from([3]));
+
+        let path = cache_path(&cache_dir, &edge_path);
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &bytes[..bytes.len() - 3]).unwrap();
+        let rebuilt = load_region_cached(&edge_path, &target_path, &cache_dir).unwrap();
+        assert!(!rebuilt.from_cache, "A truncated cache should be rebuilt");
+        assert_eq!(rebuilt.graph, changed.graph);
+    }
+}
@@ -0,0 +1,210 @@
+//! Module for exporting graphs to GraphML, GEXF and DOT files, for viewing in Gephi and Graphviz
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use crate::data_analysis::NodeStats;
+use crate::error::ReadError;
+
+/// File formats the exporters can write
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ExportFormat {
+    GraphML,
+    Gexf,
+    Dot,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 3] = [ExportFormat::GraphML, ExportFormat::Gexf, ExportFormat::Dot];
+
+    /// File extension for the format, without the dot
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::GraphML => "graphml",
+            ExportFormat::Gexf => "gexf",
+            ExportFormat::Dot => "dot",
+        }
+    }
+}
+
+/// Node attributes written by every format as (name, GraphML type, GEXF type)
+const ATTRIBUTES: [(&str, &str, &str); 7] = [
+    ("twitch_id", "long", "long"),
+    ("views", "long", "long"),
+    ("days", "long", "long"),
+    ("mature", "boolean", "boolean"),
+    ("partner", "boolean", "boolean"),
+    ("group", "int", "integer"),
+    ("groups", "string", "string"),
+];
+
+/// Builds the subgraph induced by the given nodes, keeping every edge with both ends in the set \
+/// Nodes not in the graph are left out
+pub fn induced_subgraph(graph: &HashMap<u32, HashSet<u32>>, nodes: &HashSet<u32>) -> HashMap<u32, HashSet<u32>> {
+    nodes.iter()
+        .filter_map(|node| graph.get(node).map(|neighbors| (*node, neighbors.intersection(nodes).cloned().collect())))
+        .collect()
+}
+
+/// Builds the subgraph induced by every member of the given cliques \
+/// Edges between members of different cliques are kept, so touching cliques show how they connect
+pub fn clique_union(graph: &HashMap<u32, HashSet<u32>>, cliques: &[Vec<u32>]) -> HashMap<u32, HashSet<u32>> {
+    let members: HashSet<u32> = cliques.iter().flatten().cloned().collect();
+    induced_subgraph(graph, &members)
+}
+
+/// Writes a graph to the given path in the given format, with NodeStats and group membership as node data \
+/// ### Inputs
+/// graph: HashMap graph such as the output of csv_to_hashmap, induced_subgraph or clique_union \
+/// stats: NodeStats HashMap from load_target_stats, nodes missing from it are written without those attributes \
+/// memberships: HashMap of node ids to clique or community indices, such as the output of node_memberships \
+/// ### Node Data
+/// twitch_id, views, days, mature and partner from NodeStats \
+/// group: the node's first clique or community, -1 for none, so Gephi can color nodes by partition \
+/// groups: every clique or community the node belongs to, separated by semicolons \
+/// ### Output
+/// Nodes and edges are written in id order so exports can be compared, or a ReadError if the file can't be written
+pub fn export_graph(
+    graph: &HashMap<u32, HashSet<u32>>,
+    stats: &HashMap<u32, NodeStats>,
+    memberships: &HashMap<u32, Vec<usize>>,
+    format: ExportFormat,
+    path: &str,
+) -> Result<(), ReadError> {
+    let mut nodes: Vec<u32> = graph.keys().cloned().collect();
+    nodes.sort();
+    let mut edges: Vec<(u32, u32)> = graph.iter()
+        .flat_map(|(node, neighbors)| neighbors.iter().filter(move |neighbor| node < *neighbor).map(move |neighbor| (*node, *neighbor)))
+        .collect();
+    edges.sort();
+    let attributes: Vec<Vec<(&str, String)>> = nodes.iter().map(|node| node_attributes(*node, stats, memberships)).collect();
+
+    let text = match format {
+        ExportFormat::GraphML => to_graphml(&nodes, &attributes, &edges),
+        ExportFormat::Gexf => to_gexf(&nodes, &attributes, &edges),
+        ExportFormat::Dot => to_dot(&nodes, &attributes, &edges),
+    };
+    std::fs::write(path, text).map_err(|source| ReadError::Io { path: path.to_string(), source })
+}
+
+/// Collects the attributes of one node in ATTRIBUTES order, skipping the NodeStats ones if the node has no stats
+fn node_attributes(node: u32, stats: &HashMap<u32, NodeStats>, memberships: &HashMap<u32, Vec<usize>>) -> Vec<(&'static str, String)> {
+    let mut attributes = Vec::new();
+    if let Some(node_stats) = stats.get(&node) {
+        attributes.push(("twitch_id", node_stats.id.to_string()));
+        attributes.push(("views", node_stats.views.to_string()));
+        attributes.push(("days", node_stats.days.to_string()));
+        attributes.push(("mature", node_stats.mature.to_string()));
+        attributes.push(("partner", node_stats.partner.to_string()));
+    }
+    let groups = memberships.get(&node).cloned().unwrap_or_default();
+    attributes.push(("group", groups.first().map(|group| *group as i64).unwrap_or(-1).to_string()));
+    attributes.push(("groups", groups.iter().map(|group| group.to_string()).collect::<Vec<String>>().join(";")));
+    attributes
+}
+
+/// Formats the graph as GraphML, with a key per attribute
+fn to_graphml(nodes: &[u32], attributes: &[Vec<(&str, String)>], edges: &[(u32, u32)]) -> String {
+    let mut text = String::new();
+    writeln!(text, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>").unwrap();
+    writeln!(text, "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">").unwrap();
+    for (name, graphml_type, _) in ATTRIBUTES {
+        writeln!(text, "  <key id=\"{}\" for=\"node\" attr.name=\"{}\" attr.type=\"{}\"/>", name, name, graphml_type).unwrap();
+    }
+    writeln!(text, "  <graph id=\"G\" edgedefault=\"undirected\">").unwrap();
+    for (node, node_attributes) in nodes.iter().zip(attributes) {
+        writeln!(text, "    <node id=\"n{}\">", node).unwrap();
+        for (name, value) in node_attributes {
+            writeln!(text, "      <data key=\"{}\">{}</data>", name, value).unwrap();
+        }
+        writeln!(text, "    </node>").unwrap();
+    }
+    for (start_node, end_node) in edges {
+        writeln!(text, "    <edge source=\"n{}\" target=\"n{}\"/>", start_node, end_node).unwrap();
+    }
+    writeln!(text, "  </graph>").unwrap();
+    writeln!(text, "</graphml>").unwrap();
+    text
+}
+
+/// Formats the graph as GEXF 1.3, with attribute ids given by their position in ATTRIBUTES
+fn to_gexf(nodes: &[u32], attributes: &[Vec<(&str, String)>], edges: &[(u32, u32)]) -> String {
+    let mut text = String::new();
+    writeln!(text, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>").unwrap();
+    writeln!(text, "<gexf xmlns=\"http://gexf.net/1.3\" version=\"1.3\">").unwrap();
+    writeln!(text, "  <graph mode=\"static\" defaultedgetype=\"undirected\">").unwrap();
+    writeln!(text, "    <attributes class=\"node\">").unwrap();
+    for (idx, (name, _, gexf_type)) in ATTRIBUTES.iter().enumerate() {
+        writeln!(text, "      <attribute id=\"{}\" title=\"{}\" type=\"{}\"/>", idx, name, gexf_type).unwrap();
+    }
+    writeln!(text, "    </attributes>").unwrap();
+    writeln!(text, "    <nodes>").unwrap();
+    for (node, node_attributes) in nodes.iter().zip(attributes) {
+        writeln!(text, "      <node id=\"{}\" label=\"{}\">", node, node).unwrap();
+        writeln!(text, "        <attvalues>").unwrap();
+        for (name, value) in node_attributes {
+            let idx = ATTRIBUTES.iter().position(|(attribute, _, _)| attribute == name).unwrap();
+            writeln!(text, "          <attvalue for=\"{}\" value=\"{}\"/>", idx, value).unwrap();
+        }
+        writeln!(text, "        </attvalues>").unwrap();
+        writeln!(text, "      </node>").unwrap();
+    }
+    writeln!(text, "    </nodes>").unwrap();
+    writeln!(text, "    <edges>").unwrap();
+    for (idx, (start_node, end_node)) in edges.iter().enumerate() {
+        writeln!(text, "      <edge id=\"{}\" source=\"{}\" target=\"{}\"/>", idx, start_node, end_node).unwrap();
+    }
+    writeln!(text, "    </edges>").unwrap();
+    writeln!(text, "  </graph>").unwrap();
+    writeln!(text, "</gexf>").unwrap();
+    text
+}
+
+/// Formats the graph as an undirected Graphviz DOT graph, with attributes as quoted node attributes
+fn to_dot(nodes: &[u32], attributes: &[Vec<(&str, String)>], edges: &[(u32, u32)]) -> String {
+    let mut text = String::new();
+    writeln!(text, "graph G {{").unwrap();
+    for (node, node_attributes) in nodes.iter().zip(attributes) {
+        let fields: Vec<String> = node_attributes.iter().map(|(name, value)| format!("{}=\"{}\"", name, value)).collect();
+        writeln!(text, "  {} [{}];", node, fields.join(", ")).unwrap();
+    }
+    for (start_node, end_node) in edges {
+        writeln!(text, "  {} -- {};", start_node, end_node).unwrap();
+    }
+    writeln!(text, "}}").unwrap();
+    text
+}
+
+#[cfg(test)]
+mod tests {
+use super::*;
+    use crate::random_graphs::graph_from_edges;
+    use tempfile::NamedTempFile;
+
+    /// Tests that the clique union keeps edges between cliques, and that each format writes the node data and edges
+    #[test]
+    fn test_exports() {
+        let graph = graph_from_edges(&[(1, 2), (2, 3), (1, 3), (3, 4), (4, 5)]);
+        let union = clique_union(&graph, &[vec![1, 2, 3], vec![4, 5]]);
+        assert_eq!(union, graph);
+        let subgraph = induced_subgraph(&graph, &HashSet::from([2, 3, 4]));
+        assert_eq!(subgraph[&3], HashSet::from([2, 4]));
+
+        let stats = HashMap::from([(1, NodeStats { id: 501, new_id: 1, views: 70, days: 9, mature: true, partner: false })]);
+        let memberships = HashMap::from([(1, vec![0]), (3, vec![0, 1])]);
+        let temp_file = NamedTempFile::new().unwrap();
+        let path = temp_file.path().to_str().unwrap();
+
+        export_graph(&subgraph, &stats, &memberships, ExportFormat::Dot, path).unwrap();
+        assert_eq!(std::fs::read_to_string(path).unwrap(),
+            "graph G {\n  2 [group=\"-1\", groups=\"\"];\n  3 [group=\"0\", groups=\"0;1\"];\n  4 [group=\"-1\", groups=\"\"];\n  2 -- 3;\n  3 -- 4;\n}\n");
+
+        export_graph(&graph, &stats, &memberships, ExportFormat::GraphML, path).unwrap();
+        let graphml = std::fs::read_to_string(path).unwrap();
+        assert!(graphml.contains("<data key=\"views\">70</data>"));
+        assert_eq!(graphml.matches("<edge ").count(), 5);
+
+        export_graph(&graph, &stats, &memberships, ExportFormat::Gexf, path).unwrap();
+        let gexf = std::fs::read_to_string(path).unwrap();
+        assert!(gexf.contains("<attvalue for=\"4\" value=\"false\"/>"), "Partner is the fifth attribute");
+        assert_eq!(gexf.matches("<node ").count(), 5);
+    }
+}
@@ -0,0 +1,125 @@
+//! Module for validating an edges file against its target file before analysis
+use std::collections::HashSet;
+use crate::error::ReadError;
+use crate::file_reading;
+
+/// Results of validate_graph_files, counting every problem found in the edges and target files
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ValidationReport {
+    pub edge_rows: usize,
+    pub unique_edges: usize,
+    pub self_loops: usize,
+    pub duplicate_edges: usize,
+    pub reversed_duplicates: usize,
+    pub malformed_rows: Vec<u64>,
+    pub isolated_target_nodes: usize,
+    pub missing_endpoints: Vec<u32>,
+}
+
+impl ValidationReport {
+    /// True when no problem was found, so the graph from csv_to_hashmap matches the edges file exactly
+    pub fn is_clean(&self) -> bool {
+        self.self_loops == 0
+            && self.duplicate_edges == 0
+            && self.reversed_duplicates == 0
+            && self.malformed_rows.is_empty()
+            && self.isolated_target_nodes == 0
+            && self.missing_endpoints.is_empty()
+    }
+}
+
+/// Reads an edges file and its target file, counting the problems csv_to_hashmap would silently hide \
+/// ### Checks
+/// self_loops: rows where both columns are the same node \
+/// duplicate_edges: rows repeating an earlier row exactly, such as 1,2 after 1,2 \
+/// reversed_duplicates: rows repeating an earlier row backwards, such as 2,1 after 1,2 \
+/// malformed_rows: line numbers of rows with fewer than two columns or a column that isn't a u32 \
+/// isolated_target_nodes: nodes in the target file with no edges \
+/// missing_endpoints: sorted node ids found in the edges file but not the target file \
+/// ### Output
+/// ValidationReport, or a ReadError if either file can't be opened or the target file can't be deserialized
+pub fn validate_graph_files(edge_path: &str, target_path: &str) -> Result<ValidationReport, ReadError> {
+    let mut report = ValidationReport::default();
+    let mut seen_edges: HashSet<(u32, u32)> = HashSet::new();
+    let mut edge_nodes: HashSet<u32> = HashSet::new();
+
+    let mut rdr = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .flexible(true) // Rows with the wrong number of columns are counted rather than stopping the read
+        .from_path(edge_path).map_err(|err| ReadError::from_csv(edge_path, err))?;
+    for result in rdr.records() {
+        report.edge_rows += 1;
+        let record = result.map_err(|err| ReadError::from_csv(edge_path, err))?;
+        let line = record.position().map(|position| position.line()).unwrap_or(0);
+        let parsed = (
+            record.get(0).and_then(|value| value.trim().parse::<u32>().ok()),
+            record.get(1).and_then(|value| value.trim().parse::<u32>().ok()),
+        );
+        let (Some(start_node), Some(end_node)) = parsed else {
+            report.malformed_rows.push(line);
+            continue
+        };
+
+        if start_node == end_node {
+            report.self_loops += 1;
+        }
+        if seen_edges.contains(&(start_node, end_node)) {
+            report.duplicate_edges += 1;
+        } else if seen_edges.contains(&(end_node, start_node)) {
+            report.reversed_duplicates += 1;
+        } else {
+            report.unique_edges += 1;
+        }
+        seen_edges.insert((start_node, end_node));
+        edge_nodes.insert(start_node);
+        edge_nodes.insert(end_node);
+    }
+
+    let target_nodes: HashSet<u32> = file_reading::load_target_stats(target_path)?.keys().cloned().collect();
+
+    report.isolated_target_nodes = target_nodes.difference(&edge_nodes).count();
+    report.missing_endpoints = edge_nodes.difference(&target_nodes).cloned().collect();
+    report.missing_endpoints.sort();
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    /// Tests every check on a temporary edges file containing each kind of problem
+    #[test]
+    fn test_validation() {
+        let mut temp_edge_file = NamedTempFile::new().unwrap();
+        writeln!(temp_edge_file, "from,to").unwrap();
+        writeln!(temp_edge_file, "1,2").unwrap();
+        writeln!(temp_edge_file, "1,2").unwrap();
+        writeln!(temp_edge_file, "2,1").unwrap();
+        writeln!(temp_edge_file, "3,3").unwrap();
+        writeln!(temp_edge_file, "4").unwrap();
+        writeln!(temp_edge_file, "x,1").unwrap();
+        writeln!(temp_edge_file, "2,9").unwrap();
+
+        let mut temp_target_file = NamedTempFile::new().unwrap();
+        writeln!(temp_target_file, "id,days,mature,views,partner,new_id").unwrap();
+        for new_id in [1, 2, 3, 5] {
+            writeln!(temp_target_file, "{},10,True,100,False,{}", new_id + 100, new_id).unwrap();
+        }
+
+        let report = validate_graph_files(
+            temp_edge_file.path().to_str().unwrap(),
+            temp_target_file.path().to_str().unwrap()).unwrap();
+
+        assert_eq!(report.edge_rows, 7);
+        assert_eq!(report.unique_edges, 3);
+        assert_eq!(report.self_loops, 1);
+        assert_eq!(report.duplicate_edges, 1);
+        assert_eq!(report.reversed_duplicates, 1);
+        assert_eq!(report.malformed_rows, vec![6, 7]);
+        assert_eq!(report.isolated_target_nodes, 1);
+        assert_eq!(report.missing_endpoints, vec![9]);
+        assert!(!report.is_clean());
+    }
+}
@@ -0,0 +1,115 @@
+//! Module for mapping external node ids, such as Twitch usernames or u64 ids, to the dense u32 ids used by the analyses
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// Two-way map between external node ids and dense internal ids 0, 1, 2, ... in the order ids were first seen \
+/// Graphs built from internal ids work with every analysis written for the Twitch graphs, and results can be
+/// translated back with external or external_cliques
+#[derive(Debug, Clone)]
+pub struct NodeInterner<T> {
+    ids: HashMap<T, u32>,
+    externals: Vec<T>,
+}
+
+impl<T> Default for NodeInterner<T> {
+    fn default() -> Self {
+        NodeInterner { ids: HashMap::new(), externals: Vec::new() }
+    }
+}
+
+impl<T: Eq + Hash + Clone> NodeInterner<T> {
+    pub fn new() -> Self {
+        NodeInterner::default()
+    }
+
+    /// Returns the internal id of an external id, giving it the next free internal id if it hasn't been seen
+    pub fn intern(&mut self, id: T) -> u32 {
+        if let Some(internal) = self.ids.get(&id) {
+            return *internal
+        }
+        let internal = self.externals.len() as u32;
+        self.ids.insert(id.clone(), internal);
+        self.externals.push(id);
+        internal
+    }
+
+    /// Internal id of an external id, or None if it hasn't been interned
+    pub fn get(&self, id: &T) -> Option<u32> {
+        self.ids.get(id).cloned()
+    }
+
+    /// External id of an internal id, or None if no id was given that internal id
+    pub fn external(&self, internal: u32) -> Option<&T> {
+        self.externals.get(internal as usize)
+    }
+
+    pub fn len(&self) -> usize {
+        self.externals.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.externals.is_empty()
+    }
+
+    /// Translates cliques (or any groups of nodes) of internal ids back to external ids \
+    /// Panics if an internal id wasn't given out by this interner
+    pub fn external_cliques(&self, cliques: &[Vec<u32>]) -> Vec<Vec<T>> {
+        cliques.iter()
+            .map(|clique| clique.iter().map(|internal| self.externals[*internal as usize].clone()).collect())
+            .collect()
+    }
+}
+
+/// A graph of internal ids, usable by every analysis, with the interner for translating results back to external ids
+#[derive(Debug, Clone)]
+pub struct InternedGraph<T> {
+    pub graph: HashMap<u32, HashSet<u32>>,
+    pub interner: NodeInterner<T>,
+}
+
+/// Converts a graph keyed by any id type into a graph of internal ids \
+/// Ids are interned in sorted order, including neighbors that aren't keys, so the same graph always gets the same internal ids
+pub fn intern_graph<T: Eq + Hash + Clone + Ord>(graph: &HashMap<T, HashSet<T>>) -> InternedGraph<T> {
+    let mut interner = NodeInterner::new();
+    let mut nodes: Vec<&T> = graph.keys().chain(graph.values().flatten()).collect();
+    nodes.sort();
+    nodes.dedup();
+    for node in nodes {
+        interner.intern(node.clone());
+    }
+    let internal_graph = graph.iter()
+        .map(|(node, neighbors)| (interner.ids[node], neighbors.iter().map(|neighbor| interner.ids[neighbor]).collect()))
+        .collect();
+    InternedGraph { graph: internal_graph, interner }
+}
+
+#[cfg(test)]
+mod tests {
+use super::*;
+    use crate::bron_kerbosch;
+
+    /// Tests that cliques found on interned usernames translate back, and match running Bron-Kerbosch on the names directly
+    #[test]
+    fn test_interning() {
+        let mut graph: HashMap<String, HashSet<String>> = HashMap::new();
+        for (a, b) in [("ana", "bo"), ("bo", "cy"), ("ana", "cy"), ("cy", "dee")] {
+            graph.entry(a.to_string()).or_default().insert(b.to_string());
+            graph.entry(b.to_string()).or_default().insert(a.to_string());
+        }
+
+        let InternedGraph { graph: internal_graph, interner } = intern_graph(&graph);
+        assert_eq!(interner.len(), 4);
+        assert_eq!(interner.get(&"cy".to_string()), Some(2), "Ids should be interned in sorted order");
+        assert_eq!(interner.external(3).map(|name| name.as_str()), Some("dee"));
+        assert_eq!(internal_graph[&2], HashSet::from([0, 1, 3]));
+
+        let internal_cliques = bron_kerbosch::maximal_cliques(&internal_graph, 3);
+        assert_eq!(interner.external_cliques(&internal_cliques), vec![vec!["ana", "bo", "cy"]]);
+        assert_eq!(bron_kerbosch::maximal_cliques(&graph, 3), vec![vec!["ana", "bo", "cy"]], "Bron-Kerbosch should also run on names directly");
+
+        let one_sided = HashMap::from([(5u64, HashSet::from([9, 1, 7]))]); // Neighbors that aren't keys are still interned in sorted order
+        let InternedGraph { graph: internal_graph, interner } = intern_graph(&one_sided);
+        assert_eq!((0..4).map(|internal| *interner.external(internal).unwrap()).collect::<Vec<u64>>(), vec![1, 5, 7, 9]);
+        assert_eq!(internal_graph, HashMap::from([(1, HashSet::from([0, 2, 3]))]));
+    }
+}
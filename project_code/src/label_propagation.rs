@@ -0,0 +1,191 @@
+//! Module for semi-supervised label propagation and label spreading over the friendship graph
+use std::collections::{HashMap, HashSet};
+use crate::node_classifier::{self, ClassificationMetrics};
+
+/// Graph reindexed to 0..n for the iterative updates, with nodes and neighbor lists sorted so
+/// floating point sums always happen in the same order
+struct IndexedGraph {
+    nodes: Vec<u32>,
+    neighbors: Vec<Vec<usize>>,
+}
+
+impl IndexedGraph {
+    fn new(graph: &HashMap<u32, HashSet<u32>>) -> IndexedGraph {
+        let mut nodes: Vec<u32> = graph.keys().cloned().collect();
+        nodes.sort();
+        let index: HashMap<u32, usize> = nodes.iter().enumerate().map(|(idx, node)| (*node, idx)).collect();
+        let neighbors = nodes.iter()
+            .map(|node| {
+                let mut node_neighbors: Vec<usize> = graph[node].iter().filter_map(|neighbor| index.get(neighbor).cloned()).collect();
+                node_neighbors.sort();
+                node_neighbors
+            })
+            .collect();
+        IndexedGraph { nodes, neighbors }
+    }
+}
+
+/// Runs label propagation (Zhu and Ghahramani) \
+/// ### Inputs
+/// graph: HashMap graph such as the output of csv_to_hashmap \
+/// known_labels: labels that stay fixed, every other node of the graph is predicted \
+/// max_iterations and tolerance: the updates stop after max_iterations, or once no score changes by more than tolerance \
+/// ### Algorithm Logic
+/// Known nodes start at 1.0 (positive) or 0.0 (negative), unknown nodes start at the known positive rate \
+/// Each iteration, every unknown node's score becomes the average score of its neighbors,
+/// while known nodes are clamped to their labels \
+/// ### Output
+/// HashMap of node ids to the score for every graph node, read as the probability of a positive label \
+/// Unknown nodes with no path to a known node keep the positive rate
+pub fn label_propagation(graph: &HashMap<u32, HashSet<u32>>, known_labels: &HashMap<u32, bool>, max_iterations: usize, tolerance: f64) -> HashMap<u32, f64> {
+    let indexed = IndexedGraph::new(graph);
+    let prior = positive_rate(known_labels);
+    let clamped: Vec<Option<f64>> = indexed.nodes.iter()
+        .map(|node| known_labels.get(node).map(|label| if *label { 1.0 } else { 0.0 }))
+        .collect();
+    let mut scores: Vec<f64> = clamped.iter().map(|label| label.unwrap_or(prior)).collect();
+
+    for _ in 0..max_iterations {
+        let mut largest_change: f64 = 0.0;
+        let new_scores: Vec<f64> = (0..scores.len())
+            .map(|idx| match clamped[idx] {
+                Some(label) => label,
+                None if indexed.neighbors[idx].is_empty() => scores[idx],
+                None => indexed.neighbors[idx].iter().map(|neighbor| scores[*neighbor]).sum::<f64>() / indexed.neighbors[idx].len() as f64,
+            })
+            .collect();
+        for (old, new) in scores.iter().zip(&new_scores) {
+            largest_change = largest_change.max((old - new).abs());
+        }
+        scores = new_scores;
+        if largest_change < tolerance {
+            break
+        }
+    }
+    indexed.nodes.into_iter().zip(scores).collect()
+}
+
+/// Runs label spreading (Zhou et al.) \
+/// ### Inputs
+/// graph and known_labels: as in label_propagation \
+/// alpha: share of each update that comes from the neighbors rather than the initial labels, such as 0.9 \
+/// max_iterations and tolerance: as in label_propagation \
+/// ### Algorithm Logic
+/// Each class has a score vector F, starting at 1.0 for known nodes of that class and 0.0 elsewhere (Y) \
+/// Each iteration, F = alpha * S * F + (1 - alpha) * Y, where S is the adjacency matrix normalized by
+/// 1 / sqrt(degree) on both sides \
+/// Unlike label propagation, known labels are not clamped, so noisy labels can be overridden by their neighbors \
+/// ### Output
+/// HashMap of node ids to F_positive / (F_positive + F_negative), or the known positive rate when both are 0
+pub fn label_spreading(graph: &HashMap<u32, HashSet<u32>>, known_labels: &HashMap<u32, bool>, alpha: f64, max_iterations: usize, tolerance: f64) -> HashMap<u32, f64> {
+    let indexed = IndexedGraph::new(graph);
+    let prior = positive_rate(known_labels);
+    let inverse_sqrt_degree: Vec<f64> = indexed.neighbors.iter()
+        .map(|neighbors| if neighbors.is_empty() { 0.0 } else { 1.0 / (neighbors.len() as f64).sqrt() })
+        .collect();
+
+    let mut class_scores = Vec::new();
+    for class in [true, false] {
+        let initial: Vec<f64> = indexed.nodes.iter()
+            .map(|node| if known_labels.get(node) == Some(&class) { 1.0 } else { 0.0 })
+            .collect();
+        let mut scores = initial.clone();
+        for _ in 0..max_iterations {
+            let new_scores: Vec<f64> = (0..scores.len())
+                .map(|idx| {
+                    let spread: f64 = indexed.neighbors[idx].iter()
+                        .map(|neighbor| scores[*neighbor] * inverse_sqrt_degree[*neighbor])
+                        .sum::<f64>() * inverse_sqrt_degree[idx];
+                    alpha * spread + (1.0 - alpha) * initial[idx]
+                })
+                .collect();
+            let largest_change = scores.iter().zip(&new_scores).map(|(old, new)| (old - new).abs()).fold(0.0, f64::max);
+            scores = new_scores;
+            if largest_change < tolerance {
+                break
+            }
+        }
+        class_scores.push(scores);
+    }
+
+    indexed.nodes.iter().enumerate()
+        .map(|(idx, node)| {
+            let total = class_scores[0][idx] + class_scores[1][idx];
+            (*node, if total > 0.0 { class_scores[0][idx] / total } else { prior })
+        })
+        .collect()
+}
+
+/// Share of known labels that are positive, 0.5 when nothing is known
+fn positive_rate(known_labels: &HashMap<u32, bool>) -> f64 {
+    if known_labels.is_empty() {
+        return 0.5
+    }
+    known_labels.values().filter(|label| **label).count() as f64 / known_labels.len() as f64
+}
+
+/// Which propagation method evaluate_propagation runs
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PropagationMethod {
+    Propagation,
+    Spreading { alpha: f64 },
+}
+
+/// Hides a fraction of the labels, propagates the rest over the graph and scores the recovered hidden labels \
+/// ### Inputs
+/// graph: HashMap graph such as the output of csv_to_hashmap \
+/// labels: every node's true label, such as each NodeStats' mature field \
+/// hidden_fraction and seed: share of labels hidden, split with node_classifier::train_test_split \
+/// method: label propagation or label spreading \
+/// ### Output
+/// ClassificationMetrics of the hidden nodes' scores, using a 0.5 threshold \
+/// Hidden nodes missing from the graph are scored with the known positive rate
+pub fn evaluate_propagation(
+    graph: &HashMap<u32, HashSet<u32>>,
+    labels: &HashMap<u32, bool>,
+    hidden_fraction: f64,
+    seed: u64,
+    method: PropagationMethod
+) -> ClassificationMetrics {
+    let node_ids: Vec<u32> = labels.keys().cloned().collect();
+    let (known_ids, hidden_ids) = node_classifier::train_test_split(&node_ids, hidden_fraction, seed);
+    let known_labels: HashMap<u32, bool> = known_ids.iter().map(|node| (*node, labels[node])).collect();
+
+    let scores = match method {
+        PropagationMethod::Propagation => label_propagation(graph, &known_labels, 1000, 1e-6),
+        PropagationMethod::Spreading { alpha } => label_spreading(graph, &known_labels, alpha, 1000, 1e-6),
+    };
+    let prior = positive_rate(&known_labels);
+    let predictions: Vec<(f64, bool)> = hidden_ids.iter()
+        .map(|node| (scores.get(node).cloned().unwrap_or(prior), labels[node]))
+        .collect();
+    node_classifier::classification_metrics(&predictions)
+}
+
+#[cfg(test)]
+mod tests {
+use super::*;
+    use crate::random_graphs::graph_from_edges;
+
+    /// Two triangles joined by a single edge, one labelled node in each
+    fn two_triangles() -> HashMap<u32, HashSet<u32>> {
+        graph_from_edges(&[(1, 2), (1, 3), (2, 3), (4, 5), (4, 6), (5, 6), (3, 4)])
+    }
+
+    /// Each unknown node should take the label of its own triangle
+    #[test]
+    fn test_propagation_and_spreading() {
+        let graph = two_triangles();
+        let known_labels = HashMap::from([(1, true), (6, false)]);
+
+        let propagated = label_propagation(&graph, &known_labels, 1000, 1e-9);
+        assert_eq!(propagated[&1], 1.0, "Known labels should stay clamped");
+        assert!(propagated[&2] > 0.5 && propagated[&3] > 0.5);
+        assert!(propagated[&4] < 0.5 && propagated[&5] < 0.5);
+        assert!((propagated[&2] + propagated[&5] - 1.0).abs() < 1e-6, "The graph is symmetric, so the scores should be too");
+
+        let spread = label_spreading(&graph, &known_labels, 0.9, 1000, 1e-9);
+        assert!(spread[&2] > 0.5 && spread[&3] > 0.5);
+        assert!(spread[&4] < 0.5 && spread[&5] < 0.5);
+    }
+}
@@ -0,0 +1,53 @@
+//! Library for finding maximal cliques in the Twitch Social Networks graphs and analyzing the streamers within them \
+//! The interactive program in main.rs is built on this API, and other tools can depend on it directly
+//! ### Public API
+//! Graph loading: file_reading (csv_to_hashmap, read_edge_list, load_target_stats, load_features), graph_cache, graph_validation \
+//! Node ids: interning (NodeInterner), for graphs keyed by usernames or u64 ids rather than dense u32 ids \
+//! Clique enumeration: bron_kerbosch (maximal_cliques, maximal_cliques_within with a SearchBudget, run_bron_kerbosch), weighted_cliques, relaxed_cliques (k-plexes, quasi-cliques),
+//! clique_verification, random_graphs, null_model \
+//! Long searches: progress (ProgressEvent, ProgressBar) and checkpoint (maximal_cliques_checkpointed, load_checkpoint) \
+//! Stats resolution: data_analysis (NodeStats), ego_network \
+//! Metrics: clique_percolation, clique_overlap, clique_participation, assortativity, autocorrelation,
+//! link_prediction, node_classifier, transfer_learning, label_propagation \
+//! Plotting and export: the plot_ functions of each analysis module, and graph_export \
+//! Errors: ReadError, returned by every function that reads or writes files \
+//! Helpers shared between modules, such as the search internals behind checkpointing and the classifier behind transfer learning,
+//! are pub(crate) so they can change without breaking other tools, and the main entry points are re-exported at the crate root
+//! ### Reproducibility
+//! HashMap iteration order changes between runs, so nodes, edges and values are sorted before they are shuffled, sampled or summed \
+//! Functions taking a seed therefore always give the same result for the same seed, and floating point sums match between runs
+mod error; // Only ReadError is public, re-exported below
+pub mod file_reading;
+pub mod data_analysis;
+pub mod bron_kerbosch;
+pub mod clique_percolation;
+pub mod clique_overlap;
+pub mod clique_participation;
+pub mod link_prediction;
+pub mod node_classifier;
+pub mod transfer_learning;
+pub mod label_propagation;
+pub mod assortativity;
+pub mod autocorrelation;
+pub mod ego_network;
+pub mod graph_validation;
+pub mod graph_export;
+pub mod graph_cache;
+pub mod clique_verification;
+pub mod random_graphs;
+pub mod null_model;
+pub mod interning;
+pub mod weighted_cliques;
+pub mod progress;
+pub mod checkpoint;
+pub mod relaxed_cliques;
+mod copied_alg; // Reference implementation, only compiled for tests
+
+pub use bron_kerbosch::{maximal_cliques, maximal_cliques_within, PartialCliques, SearchBudget};
+pub use checkpoint::{maximal_cliques_checkpointed, CheckpointConfig};
+pub use data_analysis::NodeStats;
+pub use error::ReadError;
+pub use file_reading::{csv_to_hashmap, read_edge_list, EdgeFormat};
+pub use graph_cache::load_region_cached;
+pub use interning::NodeInterner;
+pub use progress::{ProgressBar, ProgressEvent};
@@ -0,0 +1,222 @@
+//! Module for link prediction baselines, evaluated by holding out a fraction of each region's edges
+use std::collections::{HashMap, HashSet};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use crate::data_analysis::roc_auc;
+
+/// Train and test sets created by split_edges
+#[derive(Debug, Clone)]
+pub struct EdgeSplit {
+    pub train_graph: HashMap<u32, HashSet<u32>>,
+    pub test_edges: Vec<(u32, u32)>,
+    pub test_non_edges: Vec<(u32, u32)>,
+}
+
+/// Splits an undirected graph into a training graph and held out test edges \
+/// ### Inputs
+/// graph: HashMap graph such as the output of csv_to_hashmap \
+/// test_fraction: share of edges removed from the graph for testing, such as 0.1 \
+/// seed: seed for the random number generator, the same seed always gives the same split \
+/// ### Logic
+/// Each undirected edge is listed once as (smaller id, larger id) and shuffled \
+/// The first test_fraction of the edges are removed from the training graph \
+/// An equal number of node pairs with no edge in the full graph are sampled as negative examples \
+/// When the graph has few non-edges, they are all listed and shuffled instead of sampled \
+/// ### Output
+/// EdgeSplit where every node of the original graph is still a key of train_graph, even if its edges were all removed \
+/// test_non_edges is shorter than test_edges when the graph has fewer non-edges than test edges, such as a complete graph
+pub fn split_edges(graph: &HashMap<u32, HashSet<u32>>, test_fraction: f64, seed: u64) -> EdgeSplit {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut edges: Vec<(u32, u32)> = graph.iter()
+        .flat_map(|(node, neighbors)| neighbors.iter().filter(move |neighbor| node < *neighbor).map(move |neighbor| (*node, *neighbor)))
+        .collect();
+    edges.sort();
+    edges.shuffle(&mut rng);
+
+    let test_count = (edges.len() as f64 * test_fraction).round() as usize;
+    let test_edges: Vec<(u32, u32)> = edges[..test_count].to_vec();
+
+    let mut train_graph: HashMap<u32, HashSet<u32>> = graph.keys().map(|node| (*node, HashSet::new())).collect();
+    for (start_node, end_node) in &edges[test_count..] {
+        train_graph.get_mut(start_node).unwrap().insert(*end_node);
+        train_graph.get_mut(end_node).unwrap().insert(*start_node);
+    }
+
+    let mut nodes: Vec<u32> = graph.keys().cloned().collect();
+    nodes.sort();
+    let non_edge_count = nodes.len() * nodes.len().saturating_sub(1) / 2 - edges.len();
+    let mut test_non_edges = Vec::new();
+    if non_edge_count <= 2 * test_count { // Dense graph, rejection sampling would mostly draw edges, so list every non-edge instead
+        for (i, a) in nodes.iter().enumerate() {
+            test_non_edges.extend(nodes[i + 1..].iter().filter(|b| !graph[a].contains(b)).map(|b| (*a, *b)));
+        }
+        test_non_edges.shuffle(&mut rng);
+        test_non_edges.truncate(test_count);
+    } else {
+        let mut sampled: HashSet<(u32, u32)> = HashSet::new();
+        let mut draws_left = 100 * test_count; // At least half the non-edges are never sampled, so running out of draws is very unlikely
+        while test_non_edges.len() < test_count && draws_left > 0 {
+            draws_left -= 1;
+            let a = nodes[rng.random_range(0..nodes.len())];
+            let b = nodes[rng.random_range(0..nodes.len())];
+            let pair = (a.min(b), a.max(b));
+            if a != b && !graph[&a].contains(&b) && sampled.insert(pair) {
+                test_non_edges.push(pair);
+            }
+        }
+    }
+
+    EdgeSplit { train_graph, test_edges, test_non_edges }
+}
+
+/// Neighborhood based link prediction scores
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Predictor {
+    CommonNeighbors,
+    Jaccard,
+    AdamicAdar,
+    ResourceAllocation,
+    PreferentialAttachment,
+}
+
+impl Predictor {
+    /// Every predictor, in the order they are reported
+    pub const ALL: [Predictor; 5] = [
+        Predictor::CommonNeighbors,
+        Predictor::Jaccard,
+        Predictor::AdamicAdar,
+        Predictor::ResourceAllocation,
+        Predictor::PreferentialAttachment,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Predictor::CommonNeighbors => "Common neighbors",
+            Predictor::Jaccard => "Jaccard",
+            Predictor::AdamicAdar => "Adamic-Adar",
+            Predictor::ResourceAllocation => "Resource allocation",
+            Predictor::PreferentialAttachment => "Preferential attachment",
+        }
+    }
+
+    /// Scores how likely an edge between a and b is, higher is more likely \
+    /// Common neighbors: |N(a) ∩ N(b)| \
+    /// Jaccard: |N(a) ∩ N(b)| / |N(a) ∪ N(b)| \
+    /// Adamic-Adar: sum of 1 / ln(degree) over common neighbors \
+    /// Resource allocation: sum of 1 / degree over common neighbors \
+    /// Preferential attachment: degree(a) * degree(b)
+    pub fn score(&self, graph: &HashMap<u32, HashSet<u32>>, a: u32, b: u32) -> f64 {
+        let empty = HashSet::new();
+        let neighbors_a = graph.get(&a).unwrap_or(&empty);
+        let neighbors_b = graph.get(&b).unwrap_or(&empty);
+        let degree = |node: &u32| graph.get(node).map(|neighbors| neighbors.len()).unwrap_or(0) as f64;
+        let common = neighbors_a.intersection(neighbors_b);
+
+        match self {
+            Predictor::CommonNeighbors => common.count() as f64,
+            Predictor::Jaccard => {
+                let shared = common.count();
+                let union = neighbors_a.len() + neighbors_b.len() - shared;
+                if union == 0 { 0.0 } else { shared as f64 / union as f64 }
+            }
+            Predictor::AdamicAdar => common.map(|node| 1.0 / degree(node).ln()).sum(), // Common neighbors have degree >= 2, so ln is never 0
+            Predictor::ResourceAllocation => common.map(|node| 1.0 / degree(node)).sum(),
+            Predictor::PreferentialAttachment => neighbors_a.len() as f64 * neighbors_b.len() as f64,
+        }
+    }
+}
+
+/// Holdout performance of a single predictor
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PredictorResult {
+    pub predictor: Predictor,
+    pub auc: f64,
+    pub precision_at_k: f64,
+}
+
+/// Scores every test edge and non-edge with every predictor on the training graph \
+/// **Input:** EdgeSplit from split_edges and k, the number of top scored pairs checked for precision@k \
+/// **Output:** vector of PredictorResult in Predictor::ALL order \
+/// precision@k is the share of the k highest scored pairs that are held out edges, with non-edges ranked first on ties
+/// so predictors that score many pairs 0.0 aren't credited for the order pairs were sampled in
+pub fn evaluate_predictors(split: &EdgeSplit, k: usize) -> Vec<PredictorResult> {
+    let mut results = Vec::new();
+    for predictor in Predictor::ALL {
+        let positive_scores: Vec<f64> = split.test_edges.iter().map(|(a, b)| predictor.score(&split.train_graph, *a, *b)).collect();
+        let negative_scores: Vec<f64> = split.test_non_edges.iter().map(|(a, b)| predictor.score(&split.train_graph, *a, *b)).collect();
+
+        let mut labelled: Vec<(f64, bool)> = positive_scores.iter().map(|score| (*score, true))
+            .chain(negative_scores.iter().map(|score| (*score, false)))
+            .collect();
+        labelled.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1))); // Highest scores first, false sorts before true
+        let top_k = k.min(labelled.len());
+        let hits = labelled[..top_k].iter().filter(|(_, is_edge)| *is_edge).count();
+
+        results.push(PredictorResult {
+            predictor,
+            auc: roc_auc(&positive_scores, &negative_scores),
+            precision_at_k: if top_k == 0 { 0.0 } else { hits as f64 / top_k as f64 },
+        });
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+use super::*;
+    use crate::random_graphs::graph_from_edges;
+
+    /// Tests each predictor's score against hand-computed values
+    #[test]
+    fn test_predictor_scores() {
+        let graph = graph_from_edges(&[(1, 3), (1, 4), (2, 3), (2, 4), (2, 5), (3, 6)]);
+
+        assert_eq!(Predictor::CommonNeighbors.score(&graph, 1, 2), 2.0);
+        assert_eq!(Predictor::Jaccard.score(&graph, 1, 2), 2.0 / 3.0);
+        assert!((Predictor::AdamicAdar.score(&graph, 1, 2) - (1.0 / 3f64.ln() + 1.0 / 2f64.ln())).abs() < 1e-9);
+        assert_eq!(Predictor::ResourceAllocation.score(&graph, 1, 2), 1.0 / 3.0 + 1.0 / 2.0);
+        assert_eq!(Predictor::PreferentialAttachment.score(&graph, 1, 2), 6.0);
+    }
+
+    /// Tests that the split removes test edges from training, samples true non-edges, and is seeded
+    #[test]
+    fn test_split_edges() {
+        let edges: Vec<(u32, u32)> = (0..20).flat_map(|a| ((a + 1)..20).filter(move |b| (a + b) % 3 == 0).map(move |b| (a, b))).collect();
+        let graph = graph_from_edges(&edges);
+
+        let split = split_edges(&graph, 0.2, 7);
+        assert_eq!(split.test_edges.len(), (edges.len() as f64 * 0.2).round() as usize);
+        assert_eq!(split.test_non_edges.len(), split.test_edges.len());
+        assert_eq!(split.train_graph.len(), graph.len(), "Every node should stay in the training graph");
+        for (a, b) in &split.test_edges {
+            assert!(!split.train_graph[a].contains(b), "Test edge left in training graph");
+        }
+        for (a, b) in &split.test_non_edges {
+            assert!(!graph[a].contains(b), "Sampled non-edge is an edge");
+        }
+        assert_eq!(split.test_edges, split_edges(&graph, 0.2, 7).test_edges, "Same seed should give the same split");
+
+        let results = evaluate_predictors(&split, 5);
+        assert_eq!(results.len(), Predictor::ALL.len());
+    }
+
+    /// A complete graph has no non-edges, so the split should end without negatives instead of sampling forever,
+    /// and tied scores should rank non-edges first
+    #[test]
+    fn test_split_dense() {
+        let complete: Vec<(u32, u32)> = (0..8).flat_map(|a| ((a + 1)..8).map(move |b| (a, b))).collect();
+        let split = split_edges(&graph_from_edges(&complete), 0.25, 3);
+        assert_eq!(split.test_edges.len(), 7);
+        assert!(split.test_non_edges.is_empty());
+
+        let missing_one = graph_from_edges(&complete[1..]);
+        let split = split_edges(&missing_one, 0.25, 3);
+        assert_eq!(split.test_non_edges, vec![(0, 1)], "The only non-edge should be found");
+
+        let tied = EdgeSplit { train_graph: HashMap::new(), test_edges: vec![(1, 2)], test_non_edges: vec![(3, 4)] };
+        assert!(evaluate_predictors(&tied, 1).iter().all(|result| result.precision_at_k == 0.0), "Ties should count against the predictor");
+    }
+}
@@ -1,14 +1,20 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::io;
-mod file_reading;
-mod copied_alg;
-mod data_analysis;
-mod bron_kerbosch;
+use project_code::{
+    assortativity, autocorrelation, bron_kerbosch, checkpoint, clique_overlap, clique_participation, clique_percolation, clique_verification,
+    data_analysis, ego_network, file_reading, graph_cache, graph_export, graph_validation, label_propagation, link_prediction,
+    node_classifier, null_model, progress, random_graphs, relaxed_cliques, transfer_learning, weighted_cliques,
+};
 
+/// Seed for every randomized analysis and generator, so repeated runs print the same results
+const SEED: u64 = 42;
+
+/// Folder holding the parsed region caches, shared by every analysis that loads a region
+const CACHE_DIR: &str = "graph_cache";
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    
-    let edge_file_options = vec![
+
+    let edge_file_options = [
         "../twitch_data/DE/musae_DE_edges.csv",
         "../twitch_data/ENGB/musae_ENGB_edges.csv",
         "../twitch_data/ES/musae_ES_edges.csv",
@@ -16,8 +22,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         "../twitch_data/PTBR/musae_PTBR_edges.csv",
         "../twitch_data/RU/musae_RU_edges.csv"
     ];
-    
-    let target_file_options = vec![
+
+    let target_file_options = [
         "../twitch_data/DE/musae_DE_target.csv",
         "../twitch_data/ENGB/musae_ENGB_target.csv",
         "../twitch_data/ES/musae_ES_target.csv",
@@ -25,54 +31,759 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         "../twitch_data/PTBR/musae_PTBR_target.csv",
         "../twitch_data/RU/musae_RU_target.csv"
     ];
+
+    let features_file_options = [
+        "../twitch_data/DE/musae_DE.json",
+        "../twitch_data/ENGB/musae_ENGB_features.json",
+        "../twitch_data/ES/musae_ES_features.json",
+        "../twitch_data/FR/musae_FR_features.json",
+        "../twitch_data/PTBR/musae_PTBR_features.json",
+        "../twitch_data/RU/musae_RU_features.json"
+    ];
     // Input for setting region/language
-    let mut lang_input = String::new();
     println!("Input a number for the language you'd like to see data analyzed for:");
     println!("0: German, 1: British English, 2: Spanish, 3: French, 4: Brazilian Portuguese, 5: Russian"); // Prompts user for input
-    io::stdin().read_line(&mut lang_input).expect("Failure to read input");
-    let region_choice  = lang_input.trim().parse::<usize>().expect("Please select from the numbers provided"); // Sets region/language
+    println!("6: Another edge list, such as a SNAP graph (no streamer data, finds cliques only)");
+    println!("7: A seeded random graph (no streamer data, finds cliques only)");
+    let region_choice  = read_input().parse::<usize>().expect("Please select from the numbers provided"); // Sets region/language
+    if region_choice == edge_file_options.len() {
+        return run_edge_list_cliques()
+    }
+    if region_choice == edge_file_options.len() + 1 {
+        return run_random_graph_cliques()
+    }
+
+    // Input for selecting the analysis
+    println!("Input a number for the analysis you'd like to run:");
+    println!("0: Viewership distributions of cliques");
+    println!("1: Clique percolation communities");
+    println!("2: Clique overlap graph");
+    println!("3: Clique participation");
+    println!("4: Link prediction (all regions)");
+    println!("5: Mature classifier");
+    println!("6: Cross-region transfer learning (all regions)");
+    println!("7: Label propagation");
+    println!("8: Assortativity and homophily");
+    println!("9: Viewership autocorrelation");
+    println!("10: Single streamer report");
+    println!("11: Export graph for Gephi and Graphviz (GraphML, GEXF, DOT)");
+    println!("12: Clique counts against a degree-preserving null model");
+    println!("13: Heaviest cliques by viewership or another weight");
+    println!("14: Viewership distributions of k-plexes or quasi-cliques");
+    let analysis_choice  = read_input().parse::<usize>().expect("Please select from the numbers provided");
+
+    if !graph_cache::is_current(edge_file_options[region_choice], target_file_options[region_choice], CACHE_DIR) {
+        print_validation_report(edge_file_options[region_choice], target_file_options[region_choice])?; // Before loading, which stops at the first malformed row
+    }
+    let mut bar = progress::ProgressBar::new();
+    let region = graph_cache::load_region_cached_with_progress(edge_file_options[region_choice], target_file_options[region_choice], CACHE_DIR,
+        &mut |event| bar.update(event))?; // Creates the undirected graph
+    bar.finish();
+    let (graph, stats) = (region.graph, region.stats);
+
+    match analysis_choice {
+        0 => run_viewership_distributions(&graph, target_file_options[region_choice])?,
+        1 => run_clique_communities(&graph, &stats)?,
+        2 => run_clique_overlap(&graph)?,
+        3 => run_clique_participation(&graph, &stats)?,
+        4 => run_link_prediction(&edge_file_options, &target_file_options)?,
+        5 => run_mature_classifier(&graph, &stats, features_file_options[region_choice])?,
+        6 => run_transfer_learning(&edge_file_options, &target_file_options, &features_file_options)?,
+        7 => run_label_propagation(&graph, &stats)?,
+        8 => run_assortativity(&graph, &stats)?,
+        9 => run_autocorrelation(&graph, &stats)?,
+        10 => run_ego_report(&graph, &stats)?,
+        11 => run_graph_export(&graph, &stats)?,
+        12 => run_null_model(&graph)?,
+        13 => run_weighted_cliques(&graph, &stats)?,
+        14 => run_relaxed_cliques(&graph, target_file_options[region_choice])?,
+        _ => println!("Analysis choice not recognized. Please rerun the project to try again."),
+    }
+    Ok(())
+}
+
+/// Loads an edge list in any supported format from a path or stdin, then counts its maximal cliques \
+/// The minimum clique size and header are asked first, since reading the edges from stdin uses up the rest of the input
+fn run_edge_list_cliques() -> Result<(), Box<dyn std::error::Error>> {
+    println!("Please enter the minimum size you'd like each saved clique to be:");
+    let min_value  = read_input().parse::<u32>().expect("Please select from the numbers provided");
+    println!("Input the path of the edge list, or - to read it from stdin");
+    println!("Comma, tab or space separated files, with or without a header, # comments and gzip are detected");
+    println!("Node ids can be numbers or names, such as Twitch usernames");
+    let path = read_input();
+    println!("Does the file start with a header row, such as from,to? (y/n, or leave blank to detect it, which only works for numeric ids)");
+    let has_headers = match read_input().as_str() {
+        "y" => Some(true),
+        "n" => Some(false),
+        "" => None,
+        _ => {
+            println!("Header choice not recognized. Please rerun the project to try again.");
+            return Ok(())
+        }
+    };
+
+    let mut bar = progress::ProgressBar::new();
+    let format = file_reading::EdgeFormat { has_headers, ..file_reading::EdgeFormat::default() };
+    let interned = file_reading::read_edge_list_interned_with_progress::<String>(&path, &format,
+        &mut |event| bar.update(event))?; // Names are mapped to dense ids for the analysis
+    bar.finish();
+    let cliques = print_clique_summary(&interned.graph, min_value)?;
+    if let Some(largest) = cliques.iter().max_by_key(|clique| clique.len()) {
+        println!("Largest clique: {:?}", interned.interner.external_cliques(std::slice::from_ref(largest))[0]);
+    }
+    Ok(())
+}
 
+/// Builds one of the random_graphs generators from prompted parameters, then counts its maximal cliques \
+/// Useful for comparing the Twitch graphs against graphs with known structure, such as Moon–Moser graphs
+fn run_random_graph_cliques() -> Result<(), Box<dyn std::error::Error>> {
+    println!("Please enter the minimum size you'd like each saved clique to be:");
+    let min_value  = read_input().parse::<u32>().expect("Please select from the numbers provided");
+    println!("Input a number for the random graph you'd like to generate:");
+    println!("0: Erdős–Rényi, 1: Barabási–Albert, 2: Watts–Strogatz, 3: Stochastic block model, 4: Moon–Moser");
+    let generator = read_input().parse::<usize>().expect("Please select from the numbers provided");
+    println!("Please enter the number of nodes:");
+    let n = read_input().parse::<usize>().expect("Please enter a whole number");
+
+    let graph = match generator {
+        0 => {
+            println!("Please enter the edge probability p, such as 0.01:");
+            random_graphs::erdos_renyi(n, read_input().parse::<f64>().expect("Please enter a number"), SEED)
+        }
+        1 => {
+            println!("Please enter m, the number of edges each new node adds:");
+            random_graphs::barabasi_albert(n, read_input().parse::<usize>().expect("Please enter a whole number"), SEED)
+        }
+        2 => {
+            println!("Please enter k, the ring degree, and beta, the rewiring probability, separated by a comma:");
+            let input = read_input();
+            let (k, beta) = input.split_once(',').expect("Please enter two numbers separated by a comma");
+            let k = k.trim().parse::<usize>().expect("Please enter a whole number");
+            if k >= n {
+                println!("k must be below the number of nodes. Please rerun the project to try again.");
+                return Ok(())
+            }
+            random_graphs::watts_strogatz(n, k, beta.trim().parse::<f64>().expect("Please enter a number"), SEED)
+        }
+        3 => {
+            println!("Please enter the number of blocks, the chance of an edge within a block and between blocks, separated by commas:");
+            let values: Vec<String> = read_input().split(',').map(|value| value.trim().to_string()).collect();
+            if values.len() != 3 {
+                println!("Expected three numbers separated by commas. Please rerun the project to try again.");
+                return Ok(())
+            }
+            let blocks = values[0].parse::<usize>().expect("Please enter a whole number");
+            if blocks == 0 {
+                println!("A stochastic block model needs at least one block. Please rerun the project to try again.");
+                return Ok(())
+            }
+            let (within, between) = (values[1].parse::<f64>().expect("Please enter a number"), values[2].parse::<f64>().expect("Please enter a number"));
+            let sizes: Vec<usize> = (0..blocks).map(|block| n / blocks + usize::from(block < n % blocks)).collect(); // Spreads the remainder over the first blocks
+            let probabilities: Vec<Vec<f64>> = (0..blocks).map(|a| (0..blocks).map(|b| if a == b { within } else { between }).collect()).collect();
+            random_graphs::stochastic_block_model(&sizes, &probabilities, SEED)
+        }
+        4 => {
+            println!("A Moon–Moser graph on {} nodes has {} maximal cliques", n, random_graphs::moon_moser_clique_count(n));
+            random_graphs::moon_moser(n)
+        }
+        _ => {
+            println!("Random graph choice not recognized. Please rerun the project to try again.");
+            return Ok(())
+        }
+    };
+    print_clique_summary(&graph, min_value)?;
+    Ok(())
+}
+
+/// Prints the size of the graph, then the number of maximal cliques of at least min_value nodes and the largest clique size \
+/// Returns the cliques found
+fn print_clique_summary(graph: &HashMap<u32, HashSet<u32>>, min_value: u32) -> Result<Vec<Vec<u32>>, Box<dyn std::error::Error>> {
+    let edge_count = graph.values().map(|neighbors| neighbors.len()).sum::<usize>() / 2; // Each edge is stored at both ends
+    println!("Loaded {} nodes and {} edges", graph.len(), edge_count);
+
+    let cliques = run_budgeted_search(graph, min_value)?;
+    let largest = cliques.iter().map(|clique| clique.len()).max().unwrap_or(0);
+    println!("Found {} cliques of at least size {}, the largest containing {} nodes", cliques.len(), min_value, largest);
+    Ok(cliques)
+}
+
+/// Validates the edges file against the target file, printing a summary of any problems found \
+/// Runs before the region is parsed, so malformed rows are all listed before loading stops at the first one \
+/// Skipped when the region comes from a current cache, which is only written once both files have loaded
+fn print_validation_report(edge_path: &str, target_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let report = graph_validation::validate_graph_files(edge_path, target_path)?;
+    println!("Validated {} edge rows ({} unique edges)", report.edge_rows, report.unique_edges);
+    if report.is_clean() {
+        return Ok(())
+    }
+    println!("Validation found problems in the edges and target files:");
+    println!("    {} self-loops, {} duplicate edges, {} reversed duplicate edges (duplicates are merged when loading)",
+        report.self_loops, report.duplicate_edges, report.reversed_duplicates);
+    println!("    {} malformed rows (first lines: {:?}), which stop loading with an error", report.malformed_rows.len(),
+        &report.malformed_rows[..report.malformed_rows.len().min(10)]);
+    println!("    {} target nodes with no edges, {} edge endpoints missing from the target file (first ids: {:?})",
+        report.isolated_target_nodes, report.missing_endpoints.len(), &report.missing_endpoints[..report.missing_endpoints.len().min(10)]);
+    Ok(())
+}
+
+/// Reads a single line from stdin, trimmed of whitespace
+fn read_input() -> String {
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).expect("Failure to read input");
+    input.trim().to_string()
+}
+
+/// Prompts for which NodeStats label to predict, returning a function reading that field, or None if the choice isn't recognized
+fn read_label_choice() -> Option<fn(&data_analysis::NodeStats) -> bool> {
+    println!("Input a number for the label you'd like to predict:");
+    println!("0: Mature, 1: Partner");
+    match read_input().parse::<usize>().expect("Please select from the numbers provided") {
+        0 => Some(|node| node.mature),
+        1 => Some(|node| node.partner),
+        _ => {
+            println!("Label choice not recognized. Please rerun the project to try again.");
+            None
+        }
+    }
+}
+
+/// Prompts for the minimum clique size, then runs the Bron-Kerbosch algorithm over the whole graph \
+/// Returns the cliques sorted for consistent output, along with the minimum size used \
+/// Fails if the project was run with --verify and the cliques don't pass verification
+fn find_cliques(graph: &HashMap<u32, HashSet<u32>>) -> Result<FoundCliques, Box<dyn std::error::Error>> {
     // Input for setting minimum value
-    let mut min_input = String::new();
     println!("Please enter the minimum size you'd like each saved clique to be:"); // Prompts user for input
     println!("Note that lower numbers equals more computation time and more image files created");
-    io::stdin().read_line(&mut min_input).expect("Failure to read input");
-    let min_value  = min_input.trim().parse::<u32>().expect("Please select from the numbers provided"); // Sets minimum threshold
+    let min_value  = read_input().parse::<u32>().expect("Please select from the numbers provided"); // Sets minimum threshold
 
+    let cliques = run_budgeted_search(graph, min_value)?; // Runs the Bron-Kerbosch algorithm to find cliques of at least min_value, sorted for consistent output
+    Ok(FoundCliques { cliques, min_value })
+}
 
-    let graph = file_reading::csv_to_hashmap(edge_file_options[region_choice])?; // Creates the undirected graph
-    // Initialize the inputs for the Bron-Kerbosch Algorithm
-    let r: HashSet<u32> = HashSet::new();
-    let mut p: HashSet<u32> = graph.keys().cloned().collect();
-    let mut x: HashSet<u32> = HashSet::new();
+/// Cliques from find_cliques, and the minimum size the user asked for
+struct FoundCliques {
+    cliques: Vec<Vec<u32>>,
+    min_value: u32,
+}
 
-    let mut cliques: Vec<Vec<u32>> = Vec::new();
+/// Runs the Bron-Kerbosch algorithm within the budget set on the command line, warning if it stopped early \
+/// When run with --checkpoint-every or --resume, checkpoints the search to clique_checkpoints, and --resume continues the last checkpoint \
+/// Fails if a checkpoint can't be written, or if the project was run with --verify and the cliques don't pass verification
+fn run_budgeted_search(graph: &HashMap<u32, HashSet<u32>>, min_value: u32) -> Result<Vec<Vec<u32>>, Box<dyn std::error::Error>> {
+    let mut bar = progress::ProgressBar::new();
+    let checkpoint_config = checkpoint_from_args(graph, min_value);
+    let found = match &checkpoint_config {
+        Some(config) => {
+            let resume = if std::env::args().any(|arg| arg == "--resume") {
+                checkpoint::load_checkpoint(&config.path, graph, min_value)
+            } else {
+                None
+            };
+            match &resume {
+                Some(state) => println!("Resuming from a checkpoint with {} of {} top-level vertices done", state.processed, state.candidates.len()),
+                None => println!("Checkpointing the search to {} every {:.0?}", config.path, config.interval),
+            }
+            let mut advanced = false; // Progress is only sent once a top-level vertex is finished
+            let found = checkpoint::maximal_cliques_checkpointed(graph, min_value, budget_from_args(), config, resume,
+                &mut |event| { advanced = true; bar.update(event) })?;
+            if !found.is_complete() && !advanced {
+                println!("No top-level vertex was finished within the limits, so raise them for the next run to make progress");
+            }
+            found
+        }
+        None => bron_kerbosch::maximal_cliques_with_progress(graph, min_value, budget_from_args(), &mut |event| bar.update(event)),
+    };
+    bar.finish();
+    if let Some(limit) = found.stopped_by {
+        let reason = match limit {
+            bron_kerbosch::BudgetLimit::Time => "time limit",
+            bron_kerbosch::BudgetLimit::Calls => "recursion limit",
+            bron_kerbosch::BudgetLimit::Cliques => "clique limit",
+        };
+        println!("Search stopped at the {} after {} calls and {:.1?} spent on the search in total, so the results are incomplete", reason, found.calls, found.elapsed);
+        if checkpoint_config.is_some() {
+            println!("Rerun with --resume to continue the search from where it stopped");
+        }
+    }
+    verify_if_requested(graph, &found.cliques, min_value, found.is_complete())?;
+    Ok(found.cliques)
+}
+
+/// Reads checkpoint options from the command line, such as cargo run -- --checkpoint-every=60 \
+/// Checkpoints are written every given number of seconds, or every 60 seconds when only --resume is given \
+/// They are only written between top-level vertices, so a crash inside one slow, high degree vertex loses all the work on it \
+/// **Output:** CheckpointConfig for this graph and min_value, or None when neither option is given
+fn checkpoint_from_args(graph: &HashMap<u32, HashSet<u32>>, min_value: u32) -> Option<checkpoint::CheckpointConfig> {
+    let mut interval = None;
+    for arg in std::env::args() {
+        if let Some(seconds) = arg.strip_prefix("--checkpoint-every=") {
+            interval = Some(std::time::Duration::from_secs_f64(seconds.parse().expect("--checkpoint-every should be a number of seconds")));
+        } else if arg == "--resume" {
+            interval = interval.or(Some(std::time::Duration::from_secs(60)));
+        }
+    }
+    Some(checkpoint::CheckpointConfig { path: checkpoint::checkpoint_path("clique_checkpoints", graph, min_value), interval: interval? })
+}
 
-    bron_kerbosch::run_bron_kerbosch(&r, &mut p, &mut x, &graph, &mut cliques, min_value); // Runs the Bron-Kerbosch algorithm to find cliques of at least 10
+/// Reads search limits from the command line, such as cargo run -- --time-limit=60 --max-calls=1000000 --max-cliques=5000 \
+/// The time limit is in seconds, and limits left out are unlimited
+fn budget_from_args() -> bron_kerbosch::SearchBudget {
+    let mut budget = bron_kerbosch::SearchBudget::default();
+    for arg in std::env::args() {
+        let Some((flag, value)) = arg.split_once('=') else { continue };
+        match flag {
+            "--time-limit" => budget.time_limit = Some(std::time::Duration::from_secs_f64(value.parse().expect("--time-limit should be a number of seconds"))),
+            "--max-calls" => budget.max_calls = Some(value.parse().expect("--max-calls should be a whole number")),
+            "--max-cliques" => budget.max_cliques = Some(value.parse().expect("--max-cliques should be a whole number")),
+            _ => (),
+        }
+    }
+    budget
+}
+
+/// When the project is run with --verify (cargo run -- --verify), checks that every clique is complete, maximal and unique \
+/// Graphs small enough for brute_force_cliques are also checked for missing cliques, unless the search stopped early
+fn verify_if_requested(graph: &HashMap<u32, HashSet<u32>>, cliques: &[Vec<u32>], min_value: u32, complete: bool) -> Result<(), Box<clique_verification::VerificationReport>> {
+    if !std::env::args().any(|arg| arg == "--verify") {
+        return Ok(())
+    }
+    let (report, searched) = if complete {
+        clique_verification::check_against_brute_force(graph, cliques, min_value)
+    } else {
+        (clique_verification::verify_cliques(graph, cliques, min_value), false)
+    };
+    if !report.is_valid() {
+        return Err(Box::new(report))
+    }
+    println!("Verified {}", report);
+    if !complete {
+        println!("The search stopped early, so the graph wasn't searched for missing cliques");
+    } else if !searched {
+        println!("The graph has more than {} nodes, so it wasn't searched for missing cliques", clique_verification::BRUTE_FORCE_LIMIT);
+    }
+    Ok(())
+}
 
-    // Sort the cliques for consistent output
-    let mut sorted_cliques = cliques.clone();
-    sorted_cliques.sort();
+/// Original analysis: bar charts of each clique's viewership distribution
+fn run_viewership_distributions(graph: &HashMap<u32, HashSet<u32>>, target_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let FoundCliques { cliques: sorted_cliques, min_value } = find_cliques(graph)?;
 
     println!("Found {} cliques of at least size {}", sorted_cliques.len(), min_value);
-    println!("This will create {} image files of at most 16 charts each", (sorted_cliques.len() as f32 / 16.0).ceil());
+    plot_group_viewership(sorted_cliques, target_path)
+}
+
+/// Asks to continue, then plots the viewership distribution of each group, which can be cliques or relaxed cliques
+fn plot_group_viewership(groups: Vec<Vec<u32>>, target_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    println!("This will create {} image files of at most 16 charts each", (groups.len() as f32 / 16.0).ceil());
     println!("Would you like to continue? (y/n)");
-    let mut continue_input = String::new();
-    io::stdin().read_line(&mut continue_input).expect("Failure to read input");
-    let keep_going  = continue_input.trim().to_string(); // confirms if the user wants to continue
+    let keep_going  = read_input(); // confirms if the user wants to continue
 
-    if keep_going == "y".to_string() {   
+    if keep_going == "y" {
 
-        let node_cliques = file_reading::load_target_file_replace_u32_cliques( // Replaces the u32 cliques with NodeStats cliques 
-            target_file_options[region_choice], sorted_cliques).unwrap(); // Possible branching from here for more analysis
+        let node_cliques = file_reading::load_target_file_replace_u32_cliques( // Replaces the u32 cliques with NodeStats cliques
+            target_path, groups)?; // Possible branching from here for more analysis
 
         let viewership_dists = data_analysis::viewership_distribution(&node_cliques); // Finds the viewership distributions for each clique
 
         data_analysis::plot_viewership_distributions(viewership_dists); // Generates the viewership_distributions.png file
-    
+
     }
     else {
         println!("'y' was not selected, analysis will not progress. Please rerun the project to try again.")
     }
     Ok(())
 }
+
+/// Finds k-plexes or γ-quasi-cliques, groups missing a few friendships, then plots their viewership distributions like option 0
+fn run_relaxed_cliques(graph: &HashMap<u32, HashSet<u32>>, target_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    println!("Input a number for the kind of relaxed clique:");
+    println!("0: k-plexes (each member may miss k - 1 other members), 1: γ-quasi-cliques (each member knows a share γ of the others)");
+    let kind = read_input().parse::<usize>().expect("Please select from the numbers provided");
+    if kind > 1 {
+        println!("Relaxed clique choice not recognized. Please rerun the project to try again.");
+        return Ok(())
+    }
+    println!("Please enter the minimum size you'd like each group to be, such as 8:");
+    println!("Note that lower numbers, larger k and smaller γ take much longer");
+    let min_value = read_input().parse::<u32>().expect("Please select from the numbers provided");
+
+    let groups = if kind == 0 {
+        println!("Please enter k, such as 2:");
+        let k = read_input().parse::<usize>().expect("Please enter a whole number of at least 1");
+        if k == 0 {
+            println!("k must be at least 1, where 1 finds ordinary cliques. Please rerun the project to try again.");
+            return Ok(())
+        }
+        relaxed_cliques::maximal_k_plexes(graph, k, min_value)
+    } else {
+        println!("Please enter γ between 0.5 and 1.0, such as 0.9:");
+        let gamma = read_input().parse::<f64>().expect("Please enter a number");
+        if !(0.5..=1.0).contains(&gamma) {
+            println!("γ must be between 0.5 and 1.0. Please rerun the project to try again.");
+            return Ok(())
+        }
+        relaxed_cliques::maximal_quasi_cliques(graph, gamma, min_value)
+    };
+    let largest = groups.iter().map(|group| group.len()).max().unwrap_or(0);
+    println!("Found {} maximal groups of at least size {}, the largest containing {} nodes", groups.len(), min_value, largest);
+    plot_group_viewership(groups, target_path)
+}
+
+/// Merges the maximal cliques into overlapping k-clique communities and prints their viewership statistics
+fn run_clique_communities(graph: &HashMap<u32, HashSet<u32>>, stats: &HashMap<u32, data_analysis::NodeStats>) -> Result<(), Box<dyn std::error::Error>> {
+    let FoundCliques { cliques: sorted_cliques, min_value } = find_cliques(graph)?;
+    println!("Found {} cliques of at least size {}", sorted_cliques.len(), min_value);
+
+    println!("Please enter k, the clique size to percolate (at least 2 and at least the minimum clique size):");
+    let k = read_input().parse::<usize>().expect("Please enter a whole number");
+    if k < min_value as usize {
+        println!("k is smaller than the minimum clique size, communities would be incomplete. Please rerun the project to try again.");
+        return Ok(())
+    }
+    let Some(communities) = clique_percolation::clique_percolation(&sorted_cliques, k) else {
+        println!("k must be at least 2, since communities are built from cliques sharing k - 1 nodes. Please rerun the project to try again.");
+        return Ok(())
+    };
+    let memberships = clique_percolation::node_memberships(&communities);
+    let overlapping_nodes = memberships.values().filter(|communities| communities.len() > 1).count();
+    println!("Found {} communities covering {} nodes, {} of which belong to more than one community",
+        communities.len(), memberships.len(), overlapping_nodes);
+
+    for community in clique_percolation::community_viewership_stats(&communities, stats) {
+        println!("Community {}: {} nodes, {} total views, mean {:.1}, median {:.1}, top streamer holds {:.1}% of views, {} partners, {} mature",
+            community.community, community.size, community.total_views, community.mean_views, community.median_views,
+            community.top_share * 100.0, community.partner_count, community.mature_count);
+    }
+    Ok(())
+}
+
+/// Builds the clique overlap graph, prints its summary and draws the overlap heatmap
+fn run_clique_overlap(graph: &HashMap<u32, HashSet<u32>>) -> Result<(), Box<dyn std::error::Error>> {
+    let FoundCliques { cliques: sorted_cliques, min_value } = find_cliques(graph)?;
+    println!("Found {} cliques of at least size {}", sorted_cliques.len(), min_value);
+
+    let edges = clique_overlap::clique_overlap_graph(&sorted_cliques);
+    let summary = clique_overlap::overlap_summary(sorted_cliques.len(), &edges);
+    println!("Overlap graph: {} cliques, {} edges, density {:.4}", summary.cliques, summary.edges, summary.density);
+    println!("Mean Jaccard similarity {:.3}, max {:.3}", summary.mean_jaccard, summary.max_jaccard);
+    println!("{} cliques share no members with any other clique", summary.isolated_cliques);
+    println!("{} connected components, the largest containing {} cliques", summary.components, summary.largest_component);
+
+    let heatmap_size = sorted_cliques.len().min(200); // Caps the heatmap at 40,000 cells to keep it readable
+    if heatmap_size < sorted_cliques.len() {
+        println!("Only the first {} cliques will be drawn in the heatmap", heatmap_size);
+    }
+    clique_overlap::plot_overlap_heatmap(heatmap_size, &edges, "clique_overlap_heatmap.png"); // Generates the clique_overlap_heatmap.png file
+    Ok(())
+}
+
+/// Counts each node's clique participation, prints its correlations with the NodeStats fields and draws the scatter chart
+fn run_clique_participation(graph: &HashMap<u32, HashSet<u32>>, stats: &HashMap<u32, data_analysis::NodeStats>) -> Result<(), Box<dyn std::error::Error>> {
+    let FoundCliques { cliques: sorted_cliques, min_value } = find_cliques(graph)?;
+    println!("Found {} cliques of at least size {}", sorted_cliques.len(), min_value);
+
+    let participation = clique_participation::clique_participation(&sorted_cliques);
+    println!("{} of {} nodes belong to at least one clique", participation.len(), stats.len());
+
+    let count_correlations = clique_participation::participation_correlations(&participation, stats, |part| part.clique_count);
+    let size_correlations = clique_participation::participation_correlations(&participation, stats, |part| part.largest_clique);
+    for (name, correlations) in [("Number of cliques", count_correlations), ("Largest clique size", size_correlations)] {
+        let Some(correlations) = correlations else {
+            println!("{}: fewer than 2 nodes in the target file, so no correlations", name);
+            continue
+        };
+        println!("{}: views pearson {:.3}, log views pearson {:.3}, views spearman {:.3}, partner {:.3}, mature {:.3}",
+            name, correlations.views_pearson, correlations.log_views_pearson, correlations.views_spearman,
+            correlations.partner, correlations.mature);
+    }
+
+    clique_participation::plot_participation_scatter(&participation, stats, "clique_participation.png"); // Generates the clique_participation.png file
+    Ok(())
+}
+
+/// Holds out 10% of each region's edges and reports every link predictor's AUC and precision@k
+fn run_link_prediction(edge_paths: &[&str], target_paths: &[&str]) -> Result<(), Box<dyn std::error::Error>> {
+    println!("Please enter k, the number of top scored pairs checked for precision@k:");
+    let k = read_input().parse::<usize>().expect("Please enter a whole number");
+
+    for (path, target_path) in edge_paths.iter().zip(target_paths) {
+        let graph = graph_cache::load_region_cached(path, target_path, CACHE_DIR)?.graph;
+        let split = link_prediction::split_edges(&graph, 0.1, SEED);
+        println!("{}: {} test edges, {} test non-edges", path, split.test_edges.len(), split.test_non_edges.len());
+        for result in link_prediction::evaluate_predictors(&split, k) {
+            println!("    {:<24} AUC {:.4}, precision@{} {:.4}", result.predictor.name(), result.auc, k, result.precision_at_k);
+        }
+    }
+    Ok(())
+}
+
+/// Trains a logistic regression predicting mature from the node features, with and without graph features
+fn run_mature_classifier(graph: &HashMap<u32, HashSet<u32>>, stats: &HashMap<u32, data_analysis::NodeStats>, features_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let features = file_reading::load_features(features_path)?;
+    let labels: HashMap<u32, bool> = stats.values().map(|node| (node.new_id, node.mature)).collect();
+
+    println!("Training on 80% of {} nodes, evaluating on the remaining 20%", labels.len());
+    for (name, graph_option) in [("Node features", None), ("Node + graph features", Some(graph))] {
+        let metrics = node_classifier::evaluate_classifier(&features, &labels, graph_option, 0.2, SEED);
+        println!("{}: accuracy {:.4}, precision {:.4}, recall {:.4}, F1 {:.4}, ROC AUC {:.4}",
+            name, metrics.accuracy, metrics.precision, metrics.recall, metrics.f1, metrics.roc_auc);
+    }
+    Ok(())
+}
+
+/// Trains a mature or partner classifier on each region and prints the 6x6 transfer matrices of accuracy, F1 and ROC AUC
+fn run_transfer_learning(edge_paths: &[&str], target_paths: &[&str], features_paths: &[&str]) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(label) = read_label_choice() else { return Ok(()) };
+    println!("Would you like to include graph features? (y/n)");
+    let use_graph = read_input() == "y";
+
+    let region_names = ["DE", "ENGB", "ES", "FR", "PTBR", "RU"];
+    let mut regions = Vec::new();
+    for (idx, name) in region_names.iter().enumerate() {
+        regions.push(transfer_learning::load_region(name, edge_paths[idx], target_paths[idx], features_paths[idx], CACHE_DIR, label)?);
+    }
+
+    let matrix = transfer_learning::transfer_matrix(&regions, use_graph, SEED);
+    let metric_names = ["Accuracy", "F1", "ROC AUC"];
+    for (metric_idx, metric_name) in metric_names.iter().enumerate() {
+        println!("{} (rows: trained on, columns: evaluated on)", metric_name);
+        println!("{:>6} {}", "", region_names.iter().map(|name| format!("{:>7}", name)).collect::<Vec<String>>().join(""));
+        for (name, row) in region_names.iter().zip(&matrix) {
+            let values: Vec<f64> = row.iter().map(|metrics| [metrics.accuracy, metrics.f1, metrics.roc_auc][metric_idx]).collect();
+            println!("{:>6} {}", name, values.iter().map(|value| format!("{:>7.3}", value)).collect::<Vec<String>>().join(""));
+        }
+    }
+    Ok(())
+}
+
+/// Hides 20% of the mature or partner labels and reports how well label propagation and label spreading recover them
+fn run_label_propagation(graph: &HashMap<u32, HashSet<u32>>, stats: &HashMap<u32, data_analysis::NodeStats>) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(label) = read_label_choice() else { return Ok(()) };
+    let labels: HashMap<u32, bool> = stats.values().map(|node| (node.new_id, label(node))).collect();
+
+    println!("Hiding 20% of {} labels and propagating the rest over the friendship graph", labels.len());
+    let methods = [
+        ("Label propagation", label_propagation::PropagationMethod::Propagation),
+        ("Label spreading (alpha 0.9)", label_propagation::PropagationMethod::Spreading { alpha: 0.9 }),
+    ];
+    for (name, method) in methods {
+        let metrics = label_propagation::evaluate_propagation(graph, &labels, 0.2, SEED, method);
+        println!("{}: accuracy {:.4}, precision {:.4}, recall {:.4}, F1 {:.4}, ROC AUC {:.4}",
+            name, metrics.accuracy, metrics.precision, metrics.recall, metrics.f1, metrics.roc_auc);
+    }
+    Ok(())
+}
+
+/// Prints the mixing matrices, assortativity and edge homophily of mature, partner and views quartiles,
+/// then the numeric assortativity of views, log views, days and degree
+fn run_assortativity(graph: &HashMap<u32, HashSet<u32>>, stats: &HashMap<u32, data_analysis::NodeStats>) -> Result<(), Box<dyn std::error::Error>> {
+    let views: HashMap<u32, f64> = stats.values().map(|node| (node.new_id, node.views as f64)).collect();
+
+    let categorical = [
+        ("Mature", stats.values().map(|node| (node.new_id, node.mature as usize)).collect::<HashMap<u32, usize>>(), 2),
+        ("Partner", stats.values().map(|node| (node.new_id, node.partner as usize)).collect(), 2),
+        ("Views quartile", assortativity::quantile_bins(&views, 4), 4),
+    ];
+    for (name, categories, category_count) in categorical {
+        let matrix = assortativity::mixing_matrix(graph, &categories, category_count);
+        let (observed, expected) = assortativity::edge_homophily(&matrix);
+        println!("{}: assortativity {:.4}, edge homophily {:.4} (expected {:.4} under random mixing)",
+            name, assortativity::categorical_assortativity(&matrix), observed, expected);
+        println!("    Mixing matrix (edge ends, category 0 = False / lowest quartile):");
+        for row in &matrix {
+            println!("    {}", row.iter().map(|count| format!("{:>9}", count)).collect::<Vec<String>>().join(""));
+        }
+    }
+
+    let numeric = [
+        ("Views", views.clone()),
+        ("Log views", views.iter().map(|(node, views)| (*node, views.ln_1p())).collect()),
+        ("Days", stats.values().map(|node| (node.new_id, node.days as f64)).collect()),
+        ("Degree", graph.iter().map(|(node, neighbors)| (*node, neighbors.len() as f64)).collect()),
+    ];
+    for (name, values) in numeric {
+        match assortativity::numeric_assortativity(graph, &values) {
+            Some(coefficient) => println!("{} assortativity: {:.4}", name, coefficient),
+            None => println!("{} assortativity: no edges, so no coefficient", name),
+        }
+    }
+    Ok(())
+}
+
+/// Prints Moran's I and Geary's C of raw and log views with permutation p-values,
+/// then correlates each streamer's views with their friends' average views and draws the scatter chart
+fn run_autocorrelation(graph: &HashMap<u32, HashSet<u32>>, stats: &HashMap<u32, data_analysis::NodeStats>) -> Result<(), Box<dyn std::error::Error>> {
+    let views: HashMap<u32, f64> = stats.values().map(|node| (node.new_id, node.views as f64)).collect();
+    let log_views: HashMap<u32, f64> = views.iter().map(|(node, views)| (*node, views.ln_1p())).collect();
+
+    println!("Running 999 permutations for each measure");
+    for (name, values) in [("Views", &views), ("Log views", &log_views)] {
+        let result = autocorrelation::autocorrelation(graph, values, 999, SEED);
+        println!("{}: Moran's I {:.4} (expected {:.5}, p = {:.3}), Geary's C {:.4} (expected 1, p = {:.3})",
+            name, result.morans_i, result.expected_i, result.morans_p, result.gearys_c, result.gearys_p);
+    }
+
+    for (name, values) in [("Views", &views), ("Log views", &log_views)] {
+        let averages = autocorrelation::neighbor_averages(graph, values);
+        let mut pairs: Vec<&(f64, f64)> = averages.values().collect();
+        pairs.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+        let own: Vec<f64> = pairs.iter().map(|pair| pair.0).collect();
+        let neighbor: Vec<f64> = pairs.iter().map(|pair| pair.1).collect();
+        match (data_analysis::pearson_correlation(&own, &neighbor), data_analysis::spearman_correlation(&own, &neighbor)) {
+            (Some(pearson), Some(spearman)) => println!("{} vs friends' average: pearson {:.4}, spearman {:.4}", name, pearson, spearman),
+            _ => println!("{} vs friends' average: fewer than 2 streamers with friends, so no correlation", name),
+        }
+    }
+
+    autocorrelation::plot_neighbor_views(&autocorrelation::neighbor_averages(graph, &views), "neighbor_views.png"); // Generates the neighbor_views.png file
+    Ok(())
+}
+
+/// Looks up one streamer by new_id or Twitch id, prints their report and exports their ego network
+fn run_ego_report(graph: &HashMap<u32, HashSet<u32>>, stats: &HashMap<u32, data_analysis::NodeStats>) -> Result<(), Box<dyn std::error::Error>> {
+    println!("Input a number for the type of id you'll enter:");
+    println!("0: new_id (as in the edges file), 1: Original Twitch id");
+    let is_twitch_id = read_input().parse::<usize>().expect("Please select from the numbers provided") == 1;
+    println!("Please enter the streamer's id:");
+    let query = read_input().parse::<u32>().expect("Please enter a whole number");
+
+    let Some(node) = ego_network::resolve_node(stats, query, is_twitch_id) else {
+        println!("No streamer with id {} was found. Please rerun the project to try again.", query);
+        return Ok(())
+    };
+
+    let report = ego_network::ego_report(graph, stats, node, 3);
+    println!("Streamer {} (Twitch id {}): {} views, {} days, partner: {}, mature: {}",
+        node, report.node.id, report.node.views, report.node.days, report.node.partner, report.node.mature);
+    println!("Views rank {} of {} in the region", report.views_rank, report.region_size);
+    println!("Degree {}, clustering coefficient {:.4}, core number {}", report.degree, report.clustering, report.core_number);
+    println!("Friends: mean views {:.1}, median views {:.1}, {:.1}% partners, {:.1}% mature",
+        report.neighbor_mean_views, report.neighbor_median_views, report.neighbor_partner_share * 100.0, report.neighbor_mature_share * 100.0);
+    println!("Member of {} maximal cliques of at least size 3", report.cliques.len());
+    for clique in report.cliques.iter().take(10) {
+        println!("    Size {}: {:?}", clique.len(), clique);
+    }
+
+    let ego = ego_network::ego_network(graph, node);
+    let csv_name = format!("ego_network_{}.csv", node);
+    let png_name = format!("ego_network_{}.png", node);
+    file_reading::write_edge_csv(&ego, &csv_name)?;
+    ego_network::plot_ego_network(&ego, node, stats, &png_name);
+    println!("Ego network written to {} and {}", csv_name, png_name);
+    Ok(())
+}
+
+/// Exports the region graph, an induced subgraph or a union of cliques in every ExportFormat \
+/// Only the union of cliques needs the cliques found, so its nodes are grouped by the clique indices entered and the other exports are ungrouped
+fn run_graph_export(graph: &HashMap<u32, HashSet<u32>>, stats: &HashMap<u32, data_analysis::NodeStats>) -> Result<(), Box<dyn std::error::Error>> {
+    println!("Input a number for the part of the graph you'd like to export:");
+    println!("0: Whole region, 1: Subgraph of chosen nodes, 2: Union of chosen cliques");
+    let scope = read_input().parse::<usize>().expect("Please select from the numbers provided");
+
+    let (export, memberships, name) = match scope {
+        0 => (graph.clone(), HashMap::new(), "region_graph"),
+        1 => {
+            println!("Please enter the new_ids of the nodes to export, separated by commas:");
+            let nodes: HashSet<u32> = read_input().split(',')
+                .map(|node| node.trim().parse::<u32>().expect("Please enter whole numbers"))
+                .collect();
+            (graph_export::induced_subgraph(graph, &nodes), HashMap::new(), "subgraph")
+        }
+        2 => {
+            let FoundCliques { cliques: sorted_cliques, min_value } = find_cliques(graph)?;
+            println!("Found {} cliques of at least size {}", sorted_cliques.len(), min_value);
+            println!("Please enter the indices of the cliques to export (0 to {}), separated by commas:", sorted_cliques.len().saturating_sub(1));
+            let mut indices: Vec<usize> = read_input().split(',')
+                .map(|idx| idx.trim().parse::<usize>().expect("Please enter whole numbers"))
+                .collect();
+            indices.sort();
+            indices.dedup(); // A repeated index would list the same group twice
+            if let Some(idx) = indices.iter().find(|idx| **idx >= sorted_cliques.len()) {
+                println!("Clique index {} is out of range, there are {} cliques. Please rerun the project to try again.", idx, sorted_cliques.len());
+                return Ok(())
+            }
+            let chosen: Vec<Vec<u32>> = indices.iter().map(|idx| sorted_cliques[*idx].clone()).collect();
+            let mut memberships: HashMap<u32, Vec<usize>> = HashMap::new();
+            for idx in &indices {
+                for node in &sorted_cliques[*idx] {
+                    memberships.entry(*node).or_default().push(*idx); // Groups keep the clique indices that were typed in
+                }
+            }
+            (graph_export::clique_union(graph, &chosen), memberships, "clique_union")
+        }
+        _ => {
+            println!("Export choice not recognized. Please rerun the project to try again.");
+            return Ok(())
+        }
+    };
+
+    for format in graph_export::ExportFormat::ALL {
+        let filename = format!("{}.{}", name, format.extension());
+        graph_export::export_graph(&export, stats, &memberships, format, &filename)?;
+        println!("Exported {} nodes to {}", export.len(), filename);
+    }
+    Ok(())
+}
+
+/// Compares the region's clique counts against rewired graphs with the same degrees, printing z-scores in total and by size
+fn run_null_model(graph: &HashMap<u32, HashSet<u32>>) -> Result<(), Box<dyn std::error::Error>> {
+    println!("Please enter the minimum size you'd like each counted clique to be:");
+    let min_value = read_input().parse::<u32>().expect("Please select from the numbers provided");
+    println!("Input a number for the null model:");
+    println!("0: Double edge swaps (exact degrees), 1: Configuration model (approximate degrees, faster)");
+    let model = match read_input().parse::<usize>().expect("Please select from the numbers provided") {
+        0 => null_model::NullModel::EdgeSwap { swaps_per_edge: 10 },
+        1 => null_model::NullModel::Configuration,
+        _ => {
+            println!("Null model not recognized. Please rerun the project to try again.");
+            return Ok(())
+        }
+    };
+    println!("Please enter the number of null model graphs to build, such as 20:");
+    let samples = read_input().parse::<usize>().expect("Please enter a whole number");
+    if samples < 2 {
+        println!("At least 2 null model graphs are needed to compare against. Please rerun the project to try again.");
+        return Ok(())
+    }
+
+    let result = null_model::clique_null_model(graph, min_value, samples, model, SEED)?;
+    println!("{:<12} {:>10} {:>12} {:>10} {:>8}", "Clique size", "Observed", "Null mean", "Null std", "z");
+    for (size, score) in &result.sizes {
+        println!("{:<12} {:>10} {:>12.1} {:>10.1} {:>8.2}", size, score.observed, score.null_mean, score.null_std, score.z);
+    }
+    println!("{:<12} {:>10} {:>12.1} {:>10.1} {:>8.2}", "All", result.total.observed, result.total.null_mean, result.total.null_std, result.total.z);
+    Ok(())
+}
+
+/// Finds the clique with the most combined weight, then lists the heaviest maximal cliques
+fn run_weighted_cliques(graph: &HashMap<u32, HashSet<u32>>, stats: &HashMap<u32, data_analysis::NodeStats>) -> Result<(), Box<dyn std::error::Error>> {
+    println!("Input a number for the weight of each streamer:");
+    println!("0: Views, 1: Days, 2: Views per day");
+    let weights = match read_input().parse::<usize>().expect("Please select from the numbers provided") {
+        0 => weighted_cliques::node_weights(stats, |node| node.views as f64),
+        1 => weighted_cliques::node_weights(stats, |node| node.days as f64),
+        2 => weighted_cliques::node_weights(stats, |node| node.views as f64 / node.days.max(1) as f64), // Accounts made today count as one day old
+        _ => {
+            println!("Weight not recognized. Please rerun the project to try again.");
+            return Ok(())
+        }
+    };
+    println!("Please enter k, the number of heaviest maximal cliques to list:");
+    let k = read_input().parse::<usize>().expect("Please enter a whole number");
+
+    let (heaviest, weight) = weighted_cliques::maximum_weight_clique(graph, &weights);
+    println!("Heaviest clique: {} members, total weight {:.1}", heaviest.len(), weight);
+    for node in &heaviest {
+        match (stats.get(node), weights.get(node)) {
+            (Some(node_stats), Some(weight)) =>
+                println!("    Streamer {} (Twitch id {}): weight {:.1}, {} views, partner: {}", node, node_stats.id, weight, node_stats.views, node_stats.partner),
+            _ => println!("    Streamer {}: missing from the target file, counted with weight 0", node),
+        }
+    }
+
+    println!("Top {} maximal cliques of at least size 3 by total weight:", k);
+    for (rank, (clique, weight)) in weighted_cliques::top_weighted_cliques(graph, &weights, k, 3).iter().enumerate() {
+        println!("{}: weight {:.1}, size {}, {:?}", rank + 1, weight, clique.len(), clique);
+    }
+    Ok(())
+}
@@ -0,0 +1,234 @@
+//! Module for binary node classification, such as predicting mature, with logistic regression on sparse node features
+use std::collections::{HashMap, HashSet};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use crate::data_analysis::roc_auc;
+
+/// Sparse feature vector, pairs of (feature index, value) for every non-zero feature
+pub(crate) type SparseFeatures = Vec<(usize, f64)>;
+
+/// Number of features in the features.json files, indices run from 0 to 3169 in every region
+pub(crate) const FEATURE_COUNT: usize = 3170;
+
+/// Builds a sparse feature vector for each node \
+/// ### Inputs
+/// node_ids: the nodes to build vectors for \
+/// features: HashMap of feature lists, such as the output of load_features \
+/// graph: optional HashMap graph, adds graph-derived features when given \
+/// known_labels: labels of the training nodes, used for the neighbor label average \
+/// ### Feature Layout
+/// 0 to FEATURE_COUNT - 1: 1.0 for each feature in the node's feature list \
+/// FEATURE_COUNT: ln(1 + degree) / ln(1 + max degree), scaled to \[0, 1\] \
+/// FEATURE_COUNT + 1: share of the node's labelled neighbors that are positive,
+/// or the overall positive rate of known_labels if no neighbor is labelled \
+/// ### Output
+/// HashMap of node ids to SparseFeatures, sorted by feature index \
+///
+/// **Note** \
+/// Only known_labels are used for neighbor averages, so test labels never leak into the features
+pub(crate) fn build_feature_vectors(
+    node_ids: &[u32],
+    features: &HashMap<u32, Vec<u32>>,
+    graph: Option<&HashMap<u32, HashSet<u32>>>,
+    known_labels: &HashMap<u32, bool>
+) -> HashMap<u32, SparseFeatures> {
+    let positive_rate = if known_labels.is_empty() {
+        0.5
+    } else {
+        known_labels.values().filter(|label| **label).count() as f64 / known_labels.len() as f64
+    };
+    let max_degree = graph
+        .map(|graph| graph.values().map(|neighbors| neighbors.len()).max().unwrap_or(0))
+        .unwrap_or(0);
+
+    let mut vectors = HashMap::new();
+    for node in node_ids {
+        let mut vector: SparseFeatures = features.get(node)
+            .map(|node_features| node_features.iter()
+                .filter(|feature| (**feature as usize) < FEATURE_COUNT)
+                .map(|feature| (*feature as usize, 1.0))
+                .collect())
+            .unwrap_or_default();
+
+        if let Some(graph) = graph {
+            let empty = HashSet::new();
+            let neighbors = graph.get(node).unwrap_or(&empty);
+            let scaled_degree = if max_degree == 0 { 0.0 } else { (neighbors.len() as f64).ln_1p() / (max_degree as f64).ln_1p() };
+            let neighbor_labels: Vec<bool> = neighbors.iter().filter_map(|neighbor| known_labels.get(neighbor).cloned()).collect();
+            let neighbor_average = if neighbor_labels.is_empty() {
+                positive_rate // Falls back to the prior when no neighbor label is known
+            } else {
+                neighbor_labels.iter().filter(|label| **label).count() as f64 / neighbor_labels.len() as f64
+            };
+            vector.push((FEATURE_COUNT, scaled_degree));
+            vector.push((FEATURE_COUNT + 1, neighbor_average));
+        }
+        vectors.insert(*node, vector);
+    }
+    vectors
+}
+
+/// Splits node ids into shuffled train and test sets \
+/// The same seed always gives the same split, test_fraction is the share of nodes held out such as 0.2
+pub(crate) fn train_test_split(node_ids: &[u32], test_fraction: f64, seed: u64) -> (Vec<u32>, Vec<u32>) {
+    let mut shuffled = node_ids.to_vec();
+    shuffled.sort();
+    shuffled.shuffle(&mut StdRng::seed_from_u64(seed));
+    let test_count = (shuffled.len() as f64 * test_fraction).round() as usize;
+    let train = shuffled.split_off(test_count);
+    (train, shuffled)
+}
+
+/// Logistic regression model over sparse features
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct LogisticRegression {
+    pub weights: Vec<f64>,
+    pub bias: f64,
+}
+
+impl LogisticRegression {
+    /// Trains with stochastic gradient descent and L2 regularization \
+    /// ### Inputs
+    /// samples: pairs of (feature vector, label) \
+    /// dimensions: total number of features, FEATURE_COUNT + 2 when graph features are used \
+    /// epochs, learning_rate and l2: training settings, such as 20, 0.05 and 0.0001 \
+    /// seed: seed for the order samples are visited in each epoch \
+    /// ### Logic
+    /// For each sample, the error (predicted probability - label) is used to step the bias and
+    /// the weights of the sample's non-zero features. L2 decay is only applied to those weights,
+    /// keeping each step proportional to the number of non-zero features
+    pub fn train(samples: &[(&SparseFeatures, bool)], dimensions: usize, epochs: usize, learning_rate: f64, l2: f64, seed: u64) -> LogisticRegression {
+        let mut model = LogisticRegression { weights: vec![0.0; dimensions], bias: 0.0 };
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut order: Vec<usize> = (0..samples.len()).collect();
+        for _ in 0..epochs {
+            order.shuffle(&mut rng);
+            for idx in &order {
+                let (vector, label) = samples[*idx];
+                let error = model.predict_proba(vector) - if label { 1.0 } else { 0.0 };
+                model.bias -= learning_rate * error;
+                for (feature, value) in vector {
+                    let weight = &mut model.weights[*feature];
+                    *weight -= learning_rate * (error * value + l2 * *weight);
+                }
+            }
+        }
+        model
+    }
+
+    /// Probability that the node with the given features has a positive label
+    pub fn predict_proba(&self, vector: &SparseFeatures) -> f64 {
+        let logit = self.bias + vector.iter().map(|(feature, value)| self.weights[*feature] * value).sum::<f64>();
+        1.0 / (1.0 + (-logit).exp())
+    }
+}
+
+/// Classification metrics on a test set
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ClassificationMetrics {
+    pub accuracy: f64,
+    pub precision: f64,
+    pub recall: f64,
+    pub f1: f64,
+    pub roc_auc: f64,
+}
+
+/// Finds the accuracy, precision, recall, F1 (positive class, threshold 0.5) and ROC AUC of predicted probabilities \
+/// **Input:** pairs of (predicted probability, true label) \
+/// **Output:** ClassificationMetrics, metrics with a zero denominator are 0.0
+pub(crate) fn classification_metrics(predictions: &[(f64, bool)]) -> ClassificationMetrics {
+    let mut true_pos = 0.0;
+    let mut false_pos = 0.0;
+    let mut false_neg = 0.0;
+    let mut correct = 0.0;
+    for (probability, label) in predictions {
+        let predicted = *probability >= 0.5;
+        match (predicted, *label) {
+            (true, true) => true_pos += 1.0,
+            (true, false) => false_pos += 1.0,
+            (false, true) => false_neg += 1.0,
+            (false, false) => {}
+        }
+        if predicted == *label {
+            correct += 1.0;
+        }
+    }
+    let ratio = |numerator: f64, denominator: f64| if denominator == 0.0 { 0.0 } else { numerator / denominator };
+    let precision = ratio(true_pos, true_pos + false_pos);
+    let recall = ratio(true_pos, true_pos + false_neg);
+
+    let positive_scores: Vec<f64> = predictions.iter().filter(|(_, label)| *label).map(|(probability, _)| *probability).collect();
+    let negative_scores: Vec<f64> = predictions.iter().filter(|(_, label)| !*label).map(|(probability, _)| *probability).collect();
+    ClassificationMetrics {
+        accuracy: ratio(correct, predictions.len() as f64),
+        precision,
+        recall,
+        f1: ratio(2.0 * precision * recall, precision + recall),
+        roc_auc: roc_auc(&positive_scores, &negative_scores),
+    }
+}
+
+/// Trains a logistic regression on a random split of one region's nodes and evaluates it on the held out nodes \
+/// ### Inputs
+/// features: HashMap of feature lists, such as the output of load_features \
+/// labels: HashMap of node ids to the label being predicted, such as each NodeStats' mature field \
+/// graph: optional HashMap graph, adds degree and neighbor label average features when given \
+/// test_fraction and seed: passed to train_test_split \
+/// ### Output
+/// ClassificationMetrics on the test nodes
+pub fn evaluate_classifier(
+    features: &HashMap<u32, Vec<u32>>,
+    labels: &HashMap<u32, bool>,
+    graph: Option<&HashMap<u32, HashSet<u32>>>,
+    test_fraction: f64,
+    seed: u64
+) -> ClassificationMetrics {
+    let node_ids: Vec<u32> = labels.keys().cloned().collect();
+    let (train_ids, test_ids) = train_test_split(&node_ids, test_fraction, seed);
+    let train_labels: HashMap<u32, bool> = train_ids.iter().map(|node| (*node, labels[node])).collect();
+
+    let vectors = build_feature_vectors(&node_ids, features, graph, &train_labels);
+    let samples: Vec<(&SparseFeatures, bool)> = train_ids.iter().map(|node| (&vectors[node], labels[node])).collect();
+    let model = LogisticRegression::train(&samples, FEATURE_COUNT + 2, 20, 0.05, 0.0001, seed);
+
+    let predictions: Vec<(f64, bool)> = test_ids.iter().map(|node| (model.predict_proba(&vectors[node]), labels[node])).collect();
+    classification_metrics(&predictions)
+}
+
+#[cfg(test)]
+mod tests {
+use super::*;
+
+    /// Tests the metrics against a hand-computed confusion matrix
+    #[test]
+    fn test_classification_metrics() {
+        let predictions = vec![(0.9, true), (0.8, false), (0.3, true), (0.1, false), (0.7, true)];
+        let metrics = classification_metrics(&predictions);
+
+        assert_eq!(metrics.accuracy, 3.0 / 5.0);
+        assert_eq!(metrics.precision, 2.0 / 3.0);
+        assert_eq!(metrics.recall, 2.0 / 3.0);
+        assert!((metrics.f1 - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(metrics.roc_auc, 4.0 / 6.0);
+    }
+
+    /// A single feature decides the label, so the classifier should learn it perfectly
+    #[test]
+    fn test_classifier_learns_feature() {
+        let mut features = HashMap::new();
+        let mut labels = HashMap::new();
+        for node in 0..200u32 {
+            let label = node % 2 == 0;
+            features.insert(node, if label { vec![7, node % 5 + 10] } else { vec![node % 5 + 10] });
+            labels.insert(node, label);
+        }
+
+        let metrics = evaluate_classifier(&features, &labels, None, 0.25, 3);
+        assert_eq!(metrics.accuracy, 1.0);
+        assert_eq!(metrics.roc_auc, 1.0);
+
+        let (train, test) = train_test_split(&(0..200).collect::<Vec<u32>>(), 0.25, 3);
+        assert_eq!((train.len(), test.len()), (150, 50));
+    }
+}
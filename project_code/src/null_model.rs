@@ -0,0 +1,242 @@
+//! Module for degree-preserving null models, testing whether a graph has more or larger cliques than its degrees alone explain
+use std::collections::{BTreeMap, HashMap, HashSet};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use crate::bron_kerbosch;
+
+/// Builds a random graph from a configuration model, pairing up each node's degree worth of edge stubs at random \
+/// Pairs that would form a self-loop or repeat an edge are dropped (the erased configuration model),
+/// so high degree nodes can end up with slightly fewer edges than asked for \
+/// **Output:** HashMap graph containing every node with a degree above 0
+pub fn configuration_model(degrees: &HashMap<u32, usize>, seed: u64) -> HashMap<u32, HashSet<u32>> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut nodes: Vec<u32> = degrees.keys().cloned().collect();
+    nodes.sort();
+    let mut stubs: Vec<u32> = nodes.iter().flat_map(|node| std::iter::repeat_n(*node, degrees[node])).collect();
+    stubs.shuffle(&mut rng);
+
+    let mut graph: HashMap<u32, HashSet<u32>> = nodes.iter().filter(|node| degrees[node] > 0).map(|node| (*node, HashSet::new())).collect();
+    for pair in stubs.chunks_exact(2) {
+        if pair[0] != pair[1] {
+            graph.get_mut(&pair[0]).unwrap().insert(pair[1]);
+            graph.get_mut(&pair[1]).unwrap().insert(pair[0]);
+        }
+    }
+    graph
+}
+
+/// Rewires a graph by double edge swaps, keeping every node's degree exactly \
+/// ### Algorithm Logic
+/// Pick two edges (a, b) and (c, d) at random, and replace them with (a, d) and (c, b) \
+/// Swaps that would create a self-loop or repeat an existing edge are skipped \
+/// Stops after swaps successful swaps, or after max_tries attempts so dense graphs can't loop forever \
+/// ### Output
+/// Rewired HashMap graph, and the number of swaps made
+pub fn double_edge_swap(graph: &HashMap<u32, HashSet<u32>>, swaps: usize, max_tries: usize, seed: u64) -> (HashMap<u32, HashSet<u32>>, usize) {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut rewired = graph.clone();
+    let mut edges: Vec<(u32, u32)> = graph.iter()
+        .flat_map(|(node, neighbors)| neighbors.iter().filter(move |neighbor| node < *neighbor).map(move |neighbor| (*node, *neighbor)))
+        .collect();
+    edges.sort();
+    if edges.len() < 2 {
+        return (rewired, 0)
+    }
+
+    let mut made = 0;
+    for _ in 0..max_tries {
+        if made == swaps {
+            break
+        }
+        let (first, second) = (rng.random_range(0..edges.len()), rng.random_range(0..edges.len()));
+        let (a, b) = edges[first];
+        let (c, d) = if rng.random::<bool>() { edges[second] } else { (edges[second].1, edges[second].0) }; // Either orientation of the second edge
+        if a == d || c == b || rewired[&a].contains(&d) || rewired[&c].contains(&b) {
+            continue // Also covers picking the same edge twice, since then a == c and b == d are already linked
+        }
+        for (x, y) in [(a, b), (c, d)] {
+            rewired.get_mut(&x).unwrap().remove(&y);
+            rewired.get_mut(&y).unwrap().remove(&x);
+        }
+        for (x, y) in [(a, d), (c, b)] {
+            rewired.get_mut(&x).unwrap().insert(y);
+            rewired.get_mut(&y).unwrap().insert(x);
+        }
+        edges[first] = (a, d);
+        edges[second] = (c, b);
+        made += 1;
+    }
+    (rewired, made)
+}
+
+/// Number of maximal cliques in a graph, in total and by clique size
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CliqueProfile {
+    pub total: usize,
+    pub size_counts: BTreeMap<usize, usize>,
+}
+
+/// Runs Bron-Kerbosch over the whole graph and counts the cliques of at least min_value nodes by size
+pub fn clique_profile(graph: &HashMap<u32, HashSet<u32>>, min_value: u32) -> CliqueProfile {
+    let cliques = bron_kerbosch::maximal_cliques(graph, min_value);
+    let mut size_counts = BTreeMap::new();
+    for clique in &cliques {
+        *size_counts.entry(clique.len()).or_insert(0) += 1;
+    }
+    CliqueProfile { total: cliques.len(), size_counts }
+}
+
+/// Null models that keep each node's degree
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum NullModel {
+    /// Rewires the real graph with swaps_per_edge double edge swaps per edge, keeping degrees exactly
+    EdgeSwap { swaps_per_edge: usize },
+    /// Builds a new graph from the degrees alone with configuration_model, keeping degrees approximately
+    Configuration,
+}
+
+/// Observed count of one statistic against its distribution over the null model samples
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ZScore {
+    pub observed: f64,
+    pub null_mean: f64,
+    pub null_std: f64,
+    pub z: f64,
+}
+
+impl ZScore {
+    /// Builds the z-score of an observed value against sample values \
+    /// When every sample is the same, z is 0 if the observed value matches and infinite in its direction otherwise
+    fn from_samples(observed: f64, samples: &[f64]) -> ZScore {
+        let null_mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        let null_std = (samples.iter().map(|sample| (sample - null_mean).powi(2)).sum::<f64>() / (samples.len() as f64 - 1.0).max(1.0)).sqrt();
+        let z = if null_std > 0.0 {
+            (observed - null_mean) / null_std
+        } else if observed == null_mean {
+            0.0
+        } else {
+            (observed - null_mean).signum() * f64::INFINITY
+        };
+        ZScore { observed, null_mean, null_std, z }
+    }
+}
+
+/// Error returned by clique_null_model when fewer than 2 samples are asked for,
+/// since the sample standard deviation behind every z-score needs at least 2
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TooFewSamples {
+    pub samples: usize,
+}
+
+impl std::fmt::Display for TooFewSamples {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} null model samples asked for, at least 2 are needed for z-scores", self.samples)
+    }
+}
+
+impl std::error::Error for TooFewSamples {}
+
+/// Results of clique_null_model: the real graph's clique counts and their z-scores against the null model
+#[derive(Debug, Clone, PartialEq)]
+pub struct NullModelResult {
+    pub observed: CliqueProfile,
+    pub samples: usize,
+    pub total: ZScore,
+    pub sizes: Vec<(usize, ZScore)>, // One entry per clique size seen in the real graph or any sample, smallest first
+}
+
+/// Compares the cliques of a graph against graphs with the same degrees \
+/// ### Inputs
+/// graph: HashMap graph such as the output of csv_to_hashmap \
+/// min_value: smallest clique size counted \
+/// samples: number of null model graphs to build, such as 20 \
+/// model: NullModel to build them with \
+/// seed: sample i uses seed + i \
+/// ### Output
+/// NullModelResult, where a large positive z means the real graph has more cliques (of that size) than its degrees explain \
+/// TooFewSamples if samples is below 2, since z-scores use the sample standard deviation
+pub fn clique_null_model(graph: &HashMap<u32, HashSet<u32>>, min_value: u32, samples: usize, model: NullModel, seed: u64) -> Result<NullModelResult, TooFewSamples> {
+    if samples < 2 {
+        return Err(TooFewSamples { samples })
+    }
+    let observed = clique_profile(graph, min_value);
+    let degrees: HashMap<u32, usize> = graph.iter().map(|(node, neighbors)| (*node, neighbors.len())).collect();
+    let edge_count = degrees.values().sum::<usize>() / 2;
+
+    let profiles: Vec<CliqueProfile> = (0..samples as u64).map(|sample| {
+        let null_graph = match model {
+            NullModel::EdgeSwap { swaps_per_edge } => {
+                let swaps = swaps_per_edge * edge_count;
+                double_edge_swap(graph, swaps, swaps * 10, seed + sample).0 // Allows ten tries per swap
+            }
+            NullModel::Configuration => configuration_model(&degrees, seed + sample),
+        };
+        clique_profile(&null_graph, min_value)
+    }).collect();
+
+    let totals: Vec<f64> = profiles.iter().map(|profile| profile.total as f64).collect();
+    let mut sizes: Vec<usize> = observed.size_counts.keys().chain(profiles.iter().flat_map(|profile| profile.size_counts.keys())).cloned().collect();
+    sizes.sort();
+    sizes.dedup();
+    let count_of = |profile: &CliqueProfile, size: usize| profile.size_counts.get(&size).cloned().unwrap_or(0) as f64;
+
+    Ok(NullModelResult {
+        total: ZScore::from_samples(observed.total as f64, &totals),
+        sizes: sizes.into_iter().map(|size| {
+            let counts: Vec<f64> = profiles.iter().map(|profile| count_of(profile, size)).collect();
+            (size, ZScore::from_samples(count_of(&observed, size), &counts))
+        }).collect(),
+        observed,
+        samples,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+use super::*;
+    use crate::random_graphs;
+
+    /// Degree of every node in the graph
+    fn degrees(graph: &HashMap<u32, HashSet<u32>>) -> HashMap<u32, usize> {
+        graph.iter().map(|(node, neighbors)| (*node, neighbors.len())).collect()
+    }
+
+    /// Double edge swaps should keep every degree while changing the edges, and the configuration model should come close
+    #[test]
+    fn test_rewiring() {
+        let graph = random_graphs::barabasi_albert(200, 3, 1);
+        let (rewired, made) = double_edge_swap(&graph, 500, 5000, 2);
+        assert_eq!(made, 500);
+        assert_eq!(degrees(&rewired), degrees(&graph));
+        assert_ne!(rewired, graph);
+        assert!(rewired.iter().all(|(node, neighbors)| !neighbors.contains(node)), "Swaps shouldn't create self-loops");
+
+        let configured = configuration_model(&degrees(&graph), 3);
+        let lost: usize = degrees(&graph).iter().map(|(node, degree)| degree - configured[node].len()).sum();
+        let stubs: usize = degrees(&graph).values().sum();
+        assert!(lost * 10 < stubs, "Under a tenth of the stubs should be dropped as self-loops or repeats, lost {} of {}", lost, stubs);
+    }
+
+    /// Disjoint cliques are far more clique-heavy than their rewired versions
+    #[test]
+    fn test_clique_null_model() {
+        let probabilities: Vec<Vec<f64>> = (0..10).map(|a| (0..10).map(|b| if a == b { 1.0 } else { 0.02 }).collect()).collect();
+        let blocks = random_graphs::stochastic_block_model(&[6; 10], &probabilities, 5);
+        let result = clique_null_model(&blocks, 4, 10, NullModel::EdgeSwap { swaps_per_edge: 5 }, 6).unwrap();
+        assert!(result.observed.size_counts.keys().max().unwrap() >= &6);
+        let largest = result.sizes.iter().find(|(size, _)| *size == 6).unwrap().1;
+        assert!(largest.z > 3.0, "The real graph's 6-cliques shouldn't survive rewiring, z = {}", largest.z);
+        assert_eq!(result.samples, 10);
+    }
+
+    /// With fewer than 2 samples there is no sample standard deviation, so the model should refuse to run rather than report NaN z-scores
+    #[test]
+    fn test_too_few_samples() {
+        let graph = random_graphs::erdos_renyi(20, 0.3, 1);
+        for samples in [0, 1] {
+            assert_eq!(clique_null_model(&graph, 3, samples, NullModel::Configuration, 1), Err(TooFewSamples { samples }));
+        }
+    }
+}
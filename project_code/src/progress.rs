@@ -0,0 +1,142 @@
+//! Module for progress updates from long runs, such as clique enumeration and edge list loading, with a terminal progress bar
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+/// A progress update, passed to the on_progress callbacks of the _with_progress functions
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProgressEvent {
+    /// Bytes of an edge list read so far, out of the file size (None when reading from stdin)
+    Reading { bytes: u64, total_bytes: Option<u64>, elapsed: Duration },
+    /// Edge rows added to the graph so far, out of every row in the file
+    Parsing { rows: usize, total_rows: usize, elapsed: Duration },
+    /// Top-level vertices the Bron-Kerbosch search has finished, out of all top-level vertices, and the cliques saved so far
+    Cliques { processed: usize, total: usize, cliques: usize, elapsed: Duration },
+}
+
+impl ProgressEvent {
+    /// Share of the work done, from 0.0 to 1.0, or None when the total isn't known
+    pub fn fraction(&self) -> Option<f64> {
+        let (done, total) = match self {
+            ProgressEvent::Reading { bytes, total_bytes, .. } => (*bytes as f64, total_bytes.map(|total| total as f64)?),
+            ProgressEvent::Parsing { rows, total_rows, .. } => (*rows as f64, *total_rows as f64),
+            ProgressEvent::Cliques { processed, total, .. } => (*processed as f64, *total as f64),
+        };
+        Some(if total > 0.0 { (done / total).min(1.0) } else { 1.0 })
+    }
+
+    /// Time since the run started
+    pub fn elapsed(&self) -> Duration {
+        match self {
+            ProgressEvent::Reading { elapsed, .. } | ProgressEvent::Parsing { elapsed, .. } | ProgressEvent::Cliques { elapsed, .. } => *elapsed,
+        }
+    }
+
+    /// Estimated time left, assuming the rest of the work goes at the average rate so far \
+    /// Top-level vertices can differ widely in work, so clique estimates are rough \
+    /// None until some work is done or when the total isn't known
+    pub fn eta(&self) -> Option<Duration> {
+        let fraction = self.fraction()?;
+        if fraction <= 0.0 {
+            return None
+        }
+        Some(self.elapsed().mul_f64((1.0 - fraction) / fraction))
+    }
+
+    /// One line describing the event, such as "Finding cliques [####------] 40.0% 400/1000 vertices, 52 cliques, 1.2s elapsed, ETA 1.8s"
+    pub fn describe(&self, bar_width: usize) -> String {
+        let (label, counts) = match self {
+            ProgressEvent::Reading { bytes, total_bytes, .. } => ("Reading edges", match total_bytes {
+                Some(total) => format!("{:.1}/{:.1} MB", *bytes as f64 / 1e6, *total as f64 / 1e6),
+                None => format!("{:.1} MB", *bytes as f64 / 1e6),
+            }),
+            ProgressEvent::Parsing { rows, total_rows, .. } => ("Building graph", format!("{}/{} rows", rows, total_rows)),
+            ProgressEvent::Cliques { processed, total, cliques, .. } => ("Finding cliques", format!("{}/{} vertices, {} cliques", processed, total, cliques)),
+        };
+        let bar = match self.fraction() {
+            Some(fraction) => {
+                let filled = (fraction * bar_width as f64).round() as usize;
+                format!("[{}{}] {:5.1}% ", "#".repeat(filled), "-".repeat(bar_width - filled), fraction * 100.0)
+            }
+            None => String::new(),
+        };
+        let eta = self.eta().filter(|_| self.fraction() < Some(1.0)).map(|eta| format!(", ETA {:.1?}", eta)).unwrap_or_default(); // Finished runs show no ETA
+        format!("{} {}{}, {:.1?} elapsed{}", label, bar, counts, self.elapsed(), eta)
+    }
+}
+
+/// Progress bar drawn on one line of stderr, so it doesn't mix with results printed to stdout \
+/// Pass update as the on_progress callback, and call finish once the run is done
+#[derive(Debug, Clone, Default)]
+pub struct ProgressBar {
+    last_draw: Option<Instant>,
+    width: usize, // Length of the last line drawn, so a shorter line can blank it out
+}
+
+impl ProgressBar {
+    pub fn new() -> Self {
+        ProgressBar::default()
+    }
+
+    /// Redraws the bar, at most ten times a second except for the final event of a run
+    pub fn update(&mut self, event: &ProgressEvent) {
+        let finished = event.fraction() == Some(1.0);
+        if !finished && self.last_draw.is_some_and(|last_draw| last_draw.elapsed() < Duration::from_millis(100)) {
+            return
+        }
+        let line = event.describe(30);
+        eprint!("\r{:<width$}", line, width = self.width);
+        let _ = std::io::stderr().flush(); // A failed redraw isn't worth stopping the run for
+        self.width = line.chars().count();
+        self.last_draw = Some(Instant::now());
+    }
+
+    /// Moves past the bar, so later output starts on a new line
+    pub fn finish(&mut self) {
+        if self.last_draw.take().is_some() {
+            eprintln!();
+        }
+        self.width = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+use super::*;
+    use crate::{bron_kerbosch, file_reading, random_graphs};
+
+    /// Tests the fraction, ETA and description of events
+    #[test]
+    fn test_progress_event() {
+        let event = ProgressEvent::Cliques { processed: 25, total: 100, cliques: 7, elapsed: Duration::from_secs(10) };
+        assert_eq!(event.fraction(), Some(0.25));
+        assert_eq!(event.eta(), Some(Duration::from_secs(30)));
+        assert_eq!(event.describe(4), "Finding cliques [#---]  25.0% 25/100 vertices, 7 cliques, 10.0s elapsed, ETA 30.0s");
+
+        let stdin = ProgressEvent::Reading { bytes: 2_000_000, total_bytes: None, elapsed: Duration::from_secs(1) };
+        assert_eq!((stdin.fraction(), stdin.eta()), (None, None));
+        assert_eq!(stdin.describe(4), "Reading edges 2.0 MB, 1.0s elapsed");
+    }
+
+    /// Clique and loading events should count up to their totals, and the search should find the same cliques with progress on
+    #[test]
+    fn test_progress_callbacks() {
+        let graph = random_graphs::erdos_renyi(60, 0.2, 3);
+        let mut events = Vec::new();
+        let found = bron_kerbosch::maximal_cliques_with_progress(&graph, 3, bron_kerbosch::SearchBudget::default(), &mut |event| events.push(event.clone()));
+        assert_eq!(found.cliques, bron_kerbosch::maximal_cliques(&graph, 3));
+        let counts: Vec<(usize, usize, usize)> = events.iter().map(|event| match event {
+            ProgressEvent::Cliques { processed, total, cliques, .. } => (*processed, *total, *cliques),
+            _ => panic!("Only clique events expected"),
+        }).collect();
+        assert!(counts.windows(2).all(|pair| pair[0].0 < pair[1].0 && pair[0].2 <= pair[1].2));
+        let (processed, total, cliques) = *counts.last().unwrap();
+        assert_eq!((processed, cliques), (total, found.cliques.len()));
+
+        let mut events = Vec::new();
+        let loaded = file_reading::read_edge_list_with_progress("../twitch_data/ENGB/musae_ENGB_edges.csv", &file_reading::EdgeFormat::default(),
+            &mut |event| events.push(event.clone())).unwrap();
+        assert_eq!(loaded, file_reading::csv_to_hashmap("../twitch_data/ENGB/musae_ENGB_edges.csv").unwrap());
+        assert!(matches!(events.first(), Some(ProgressEvent::Reading { .. })));
+        assert!(matches!(events.last(), Some(ProgressEvent::Parsing { rows, total_rows, .. }) if rows == total_rows));
+    }
+}
@@ -0,0 +1,220 @@
+//! Module for generating seeded random graphs, in the same HashMap form as csv_to_hashmap, for testing and null models
+use std::collections::{HashMap, HashSet};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::Rng;
+
+/// Adds an undirected edge to the graph, ignoring self-loops
+fn add_edge(graph: &mut HashMap<u32, HashSet<u32>>, a: u32, b: u32) {
+    if a == b {
+        return
+    }
+    graph.entry(a).or_default().insert(b);
+    graph.entry(b).or_default().insert(a);
+}
+
+/// Builds a small undirected graph from an edge list, shared by the tests of every module
+#[cfg(test)]
+pub(crate) fn graph_from_edges(edges: &[(u32, u32)]) -> HashMap<u32, HashSet<u32>> {
+    let mut graph = HashMap::new();
+    for (a, b) in edges {
+        add_edge(&mut graph, *a, *b);
+    }
+    graph
+}
+
+/// Graph with nodes 0 to n - 1 and no edges, so isolated nodes are kept as keys with empty sets
+fn empty_graph(n: usize) -> HashMap<u32, HashSet<u32>> {
+    (0..n as u32).map(|node| (node, HashSet::new())).collect()
+}
+
+/// Builds an Erdős–Rényi G(n, p) graph, where each of the n(n - 1) / 2 possible edges exists with probability p \
+/// **Output:** HashMap graph with nodes 0 to n - 1, including nodes with no edges
+pub fn erdos_renyi(n: usize, p: f64, seed: u64) -> HashMap<u32, HashSet<u32>> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut graph = empty_graph(n);
+    for a in 0..n as u32 {
+        for b in a + 1..n as u32 {
+            if rng.random::<f64>() < p {
+                add_edge(&mut graph, a, b);
+            }
+        }
+    }
+    graph
+}
+
+/// Builds a Barabási–Albert preferential attachment graph \
+/// ### Algorithm Logic
+/// Start from a complete graph on the first m + 1 nodes \
+/// Each later node connects to m distinct earlier nodes, each picked with probability proportional to its degree \
+/// ### Output
+/// HashMap graph with nodes 0 to n - 1 and m(m + 1) / 2 + (n - m - 1) * m edges, a few hubs and many low degree nodes like the Twitch graphs
+pub fn barabasi_albert(n: usize, m: usize, seed: u64) -> HashMap<u32, HashSet<u32>> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut graph = empty_graph(n);
+    let start = (m + 1).min(n) as u32;
+    let mut endpoints: Vec<u32> = Vec::new(); // Every node once per edge it has, so uniform picks are degree-weighted
+    for a in 0..start {
+        for b in a + 1..start {
+            add_edge(&mut graph, a, b);
+            endpoints.extend([a, b]);
+        }
+    }
+    for node in start..n as u32 {
+        let mut targets: HashSet<u32> = HashSet::new();
+        while targets.len() < m {
+            targets.insert(endpoints[rng.random_range(0..endpoints.len())]);
+        }
+        let mut targets: Vec<u32> = targets.into_iter().collect();
+        targets.sort();
+        for target in targets {
+            add_edge(&mut graph, node, target);
+            endpoints.extend([node, target]);
+        }
+    }
+    graph
+}
+
+/// Builds a Watts–Strogatz small-world graph \
+/// ### Algorithm Logic
+/// Start from a ring where each node connects to its k / 2 nearest nodes on each side \
+/// Each ring edge (a, a + j) is then moved, with probability beta, to (a, random node), skipping moves that would
+/// create a self-loop or repeat an edge \
+/// ### Output
+/// HashMap graph with nodes 0 to n - 1 and n * (k / 2) edges, a lattice at beta = 0 and close to random at beta = 1 \
+/// Panics if k is not below n, since the ring would wrap onto itself with self-loops and repeated edges
+pub fn watts_strogatz(n: usize, k: usize, beta: f64, seed: u64) -> HashMap<u32, HashSet<u32>> {
+    assert!(k < n, "Watts–Strogatz graphs need k below n");
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut graph = empty_graph(n);
+    let n32 = n as u32;
+    for j in 1..=(k / 2) as u32 {
+        for a in 0..n32 {
+            add_edge(&mut graph, a, (a + j) % n32);
+        }
+    }
+    for j in 1..=(k / 2) as u32 {
+        for a in 0..n32 {
+            let b = (a + j) % n32;
+            if rng.random::<f64>() >= beta || !graph[&a].contains(&b) {
+                continue
+            }
+            let new_b = rng.random_range(0..n32);
+            if new_b == a || graph[&a].contains(&new_b) {
+                continue
+            }
+            graph.get_mut(&a).unwrap().remove(&b);
+            graph.get_mut(&b).unwrap().remove(&a);
+            add_edge(&mut graph, a, new_b);
+        }
+    }
+    graph
+}
+
+/// Builds a stochastic block model graph, where the chance of an edge depends only on the blocks of its two nodes \
+/// ### Inputs
+/// sizes: number of nodes in each block, nodes are numbered block by block starting from 0 \
+/// probabilities: symmetric matrix where probabilities[i][j] is the chance of an edge between a node in block i and one in block j \
+/// seed: seed for the edges \
+/// ### Output
+/// HashMap graph with sizes.iter().sum() nodes, such as dense communities with sparse links between them
+pub fn stochastic_block_model(sizes: &[usize], probabilities: &[Vec<f64>], seed: u64) -> HashMap<u32, HashSet<u32>> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let blocks: Vec<usize> = sizes.iter().enumerate().flat_map(|(block, size)| std::iter::repeat_n(block, *size)).collect();
+    let mut graph = empty_graph(blocks.len());
+    for a in 0..blocks.len() {
+        for b in a + 1..blocks.len() {
+            if rng.random::<f64>() < probabilities[blocks[a]][blocks[b]] {
+                add_edge(&mut graph, a as u32, b as u32);
+            }
+        }
+    }
+    graph
+}
+
+/// Sizes of the groups in the Moon–Moser graph on n nodes: threes, with one four or one two for the remainder
+fn moon_moser_groups(n: usize) -> Vec<usize> {
+    match n {
+        0 => Vec::new(),
+        1 => vec![1],
+        _ => {
+            let (threes, last) = match n % 3 {
+                0 => (n / 3, None),
+                1 => ((n - 4) / 3, Some(4)),
+                _ => ((n - 2) / 3, Some(2)),
+            };
+            let mut groups = vec![3; threes];
+            groups.extend(last);
+            groups
+        }
+    }
+}
+
+/// Builds the Moon–Moser graph on n nodes, which has the most maximal cliques of any graph with n nodes \
+/// Nodes are split into groups of three (with one group of four or two if n isn't a multiple of 3), and every
+/// node is connected to every node outside its own group, so each maximal clique takes exactly one node per group \
+/// **Output:** HashMap graph with moon_moser_clique_count(n) maximal cliques, each of moon_moser_groups(n).len() nodes
+pub fn moon_moser(n: usize) -> HashMap<u32, HashSet<u32>> {
+    let groups: Vec<usize> = moon_moser_groups(n).iter().enumerate().flat_map(|(group, size)| std::iter::repeat_n(group, *size)).collect();
+    let mut graph = empty_graph(n);
+    for a in 0..n {
+        for b in a + 1..n {
+            if groups[a] != groups[b] {
+                add_edge(&mut graph, a as u32, b as u32);
+            }
+        }
+    }
+    graph
+}
+
+/// Number of maximal cliques in moon_moser(n): 3^(n/3), 4 * 3^((n-4)/3) or 2 * 3^((n-2)/3) depending on n % 3
+pub fn moon_moser_clique_count(n: usize) -> u64 {
+    moon_moser_groups(n).iter().map(|size| *size as u64).product()
+}
+
+#[cfg(test)]
+mod tests {
+use super::*;
+    use crate::bron_kerbosch;
+
+    /// Runs Bron-Kerbosch over the whole graph, keeping cliques of at least min_value nodes
+    fn all_cliques(graph: &HashMap<u32, HashSet<u32>>, min_value: u32) -> Vec<Vec<u32>> {
+        let mut cliques = Vec::new();
+        let mut p: HashSet<u32> = graph.keys().cloned().collect();
+        bron_kerbosch::run_bron_kerbosch(&HashSet::new(), &mut p, &mut HashSet::new(), graph, &mut cliques, min_value);
+        cliques
+    }
+
+    /// Moon–Moser graphs should have exactly their known number of maximal cliques
+    #[test]
+    fn test_moon_moser() {
+        for n in 2..=11 {
+            let graph = moon_moser(n);
+            assert_eq!(all_cliques(&graph, 1).len() as u64, moon_moser_clique_count(n), "Clique count for n = {}", n);
+        }
+        assert_eq!(moon_moser_clique_count(12), 81);
+    }
+
+    /// Tests edge counts, seeding and the extreme probabilities of the other generators
+    #[test]
+    fn test_generators() {
+        let edge_count = |graph: &HashMap<u32, HashSet<u32>>| graph.values().map(|neighbors| neighbors.len()).sum::<usize>() / 2;
+
+        assert_eq!(edge_count(&erdos_renyi(10, 1.0, 1)), 45);
+        assert_eq!(erdos_renyi(50, 0.1, 7), erdos_renyi(50, 0.1, 7), "The same seed should give the same graph");
+        assert_ne!(erdos_renyi(50, 0.1, 7), erdos_renyi(50, 0.1, 8));
+
+        let barabasi = barabasi_albert(100, 3, 2);
+        assert_eq!((barabasi.len(), edge_count(&barabasi)), (100, 6 + 96 * 3));
+
+        let lattice = watts_strogatz(20, 4, 0.0, 3);
+        assert!(lattice.values().all(|neighbors| neighbors.len() == 4));
+        assert_eq!(edge_count(&watts_strogatz(20, 4, 0.5, 3)), 40, "Rewiring should keep the number of edges");
+        assert_eq!(edge_count(&watts_strogatz(5, 4, 0.0, 3)), 10, "The largest allowed k gives a complete graph");
+
+        let blocks = stochastic_block_model(&[4, 3], &[vec![1.0, 0.0], vec![0.0, 1.0]], 4);
+        let mut cliques = all_cliques(&blocks, 1);
+        cliques.sort();
+        assert_eq!(cliques, vec![vec![0, 1, 2, 3], vec![4, 5, 6]]);
+    }
+}
@@ -0,0 +1,322 @@
+//! Module for finding relaxed cliques, k-plexes and γ-quasi-cliques, which still count groups missing a few friendships
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// True when every member is connected to all but at most k - 1 other members, counting itself as missing (a k-plex) \
+/// A 1-plex is a clique
+pub fn is_k_plex(graph: &HashMap<u32, HashSet<u32>>, members: &[u32], k: usize) -> bool {
+    members.iter().all(|node| inner_degree(graph, node, members) + k >= members.len())
+}
+
+/// True when every member is connected to at least gamma * (size - 1) other members (a γ-quasi-clique) \
+/// A 1.0-quasi-clique is a clique
+pub fn is_quasi_clique(graph: &HashMap<u32, HashSet<u32>>, members: &[u32], gamma: f64) -> bool {
+    let needed = quasi_degree(gamma, members.len());
+    members.iter().all(|node| inner_degree(graph, node, members) >= needed)
+}
+
+/// Finds every maximal k-plex of at least min_value nodes \
+/// ### Inputs
+/// graph: HashMap graph such as the output of csv_to_hashmap \
+/// k: number of members each member may be missing, counting itself, so 1 finds cliques and 2 allows one missing friendship per member \
+/// min_value: smallest k-plex kept, raised to 2k - 1 if lower, since smaller k-plexes can be disconnected \
+/// ### Algorithm Logic
+/// Nodes with fewer than min_value - k neighbors can't be in a large enough k-plex, so they are removed first \
+/// k-plexes of at least 2k - 1 nodes have every pair of members at most two hops apart and sharing min_value - 2k neighbors,
+/// so each node only searches nearby nodes, as the earliest member in a degree ordering \
+/// Bron-Kerbosch then runs on that neighborhood, where a candidate joins if the group is still a k-plex,
+/// and earlier nodes are only used to rule out groups that aren't maximal \
+/// ### Output
+/// Sorted k-plexes, each sorted, in the same form as bron_kerbosch::maximal_cliques \
+/// Panics if k is 0
+pub fn maximal_k_plexes(graph: &HashMap<u32, HashSet<u32>>, k: usize, min_value: u32) -> Vec<Vec<u32>> {
+    assert!(k >= 1, "k-plexes need k of at least 1");
+    let min_size = (min_value as usize).max(2 * k - 1);
+    let pruned = prune_to_core(graph, min_size.saturating_sub(k));
+    let min_common = min_size.saturating_sub(2 * k);
+
+    let mut plexes = Vec::new();
+    for (later, earlier, seed) in seed_neighborhoods(&pruned, min_common) {
+        let mut members = vec![seed];
+        let later = later.into_iter().filter(|node| can_join_plex(&pruned, &members, node, k)).collect();
+        let earlier = earlier.into_iter().filter(|node| can_join_plex(&pruned, &members, node, k)).collect();
+        expand_plex(&pruned, k, min_size, &mut members, later, earlier, &mut plexes);
+    }
+    plexes.sort();
+    plexes
+}
+
+/// Finds every maximal γ-quasi-clique of at least min_value nodes \
+/// ### Inputs
+/// graph: HashMap graph such as the output of csv_to_hashmap \
+/// gamma: share of the other members each member must be connected to, from 0.5 to 1.0, where 1.0 finds cliques \
+/// min_value: smallest quasi-clique kept \
+/// ### Algorithm Logic
+/// Quasi-cliques aren't closed under removing members, so the search can't stop at the first group that fails \
+/// Nodes with fewer than gamma * (min_value - 1) neighbors are removed first, and for gamma of at least 0.5 every pair of members
+/// is at most two hops apart, so each node searches the nearby nodes after it in a degree ordering \
+/// Groups are grown one candidate at a time, dropping candidates that can't reach enough members,
+/// and skipping branches where some member can't reach enough nodes for a quasi-clique of min_value \
+/// Groups that no single node extends are kept, then any contained in a larger kept group are removed \
+/// ### Output
+/// Sorted quasi-cliques, each sorted, in the same form as bron_kerbosch::maximal_cliques \
+/// Panics if gamma is below 0.5 or above 1.0
+pub fn maximal_quasi_cliques(graph: &HashMap<u32, HashSet<u32>>, gamma: f64, min_value: u32) -> Vec<Vec<u32>> {
+    assert!((0.5..=1.0).contains(&gamma), "gamma must be between 0.5 and 1.0, lower values allow disconnected quasi-cliques");
+    let min_size = (min_value as usize).max(1);
+    let pruned = prune_to_core(graph, quasi_degree(gamma, min_size));
+    // Two members share at least 2 * gamma * (size - 1) - size neighbors, which only grows with size when gamma >= 0.5
+    let min_common = (2.0 * gamma * (min_size as f64 - 1.0) - min_size as f64 - 1e-9).ceil().max(0.0) as usize;
+
+    let mut found = Vec::new();
+    for (later, _, seed) in seed_neighborhoods(&pruned, min_common) {
+        expand_quasi(&pruned, gamma, min_size, &mut vec![seed], later, &mut found);
+    }
+
+    // Keeps the groups not contained in a larger one, checking the largest groups first
+    found.sort_by(|a, b| b.len().cmp(&a.len()).then(a.cmp(b)));
+    let mut kept: Vec<(Vec<u32>, HashSet<u32>)> = Vec::new();
+    for group in found {
+        if !kept.iter().any(|(larger, set)| larger.len() > group.len() && group.iter().all(|node| set.contains(node))) {
+            let set = group.iter().cloned().collect();
+            kept.push((group, set));
+        }
+    }
+    let mut quasi_cliques: Vec<Vec<u32>> = kept.into_iter().map(|(group, _)| group).collect();
+    quasi_cliques.sort();
+    quasi_cliques
+}
+
+/// Number of members of the group connected to node
+fn inner_degree(graph: &HashMap<u32, HashSet<u32>>, node: &u32, members: &[u32]) -> usize {
+    members.iter().filter(|member| graph[node].contains(member)).count()
+}
+
+/// Number of nodes in pool connected to node, searching whichever of the two is smaller
+fn pool_degree(graph: &HashMap<u32, HashSet<u32>>, node: &u32, pool: &HashSet<u32>) -> usize {
+    if graph[node].len() < pool.len() {
+        graph[node].iter().filter(|neighbor| pool.contains(neighbor)).count()
+    } else {
+        pool.iter().filter(|other| graph[node].contains(other)).count()
+    }
+}
+
+/// Fewest connections each member of a γ-quasi-clique of size nodes needs, with slack for rounding errors
+fn quasi_degree(gamma: f64, size: usize) -> usize {
+    (gamma * size.saturating_sub(1) as f64 - 1e-9).ceil().max(0.0) as usize
+}
+
+/// Repeatedly removes nodes with fewer than min_degree neighbors, returning the rest of the graph (its min_degree-core)
+fn prune_to_core(graph: &HashMap<u32, HashSet<u32>>, min_degree: usize) -> HashMap<u32, HashSet<u32>> {
+    let mut pruned = graph.clone();
+    let mut queue: VecDeque<u32> = pruned.iter().filter(|(_, neighbors)| neighbors.len() < min_degree).map(|(node, _)| *node).collect();
+    while let Some(node) = queue.pop_front() {
+        let Some(neighbors) = pruned.remove(&node) else { continue }; // Already removed
+        for neighbor in neighbors {
+            if let Some(others) = pruned.get_mut(&neighbor) {
+                others.remove(&node);
+                if others.len() + 1 == min_degree {
+                    queue.push_back(neighbor); // Only queued once, when it first drops below min_degree
+                }
+            }
+        }
+    }
+    pruned
+}
+
+/// For each node in order of degree, the nodes at most two hops away sharing at least min_common neighbors with it \
+/// **Output:** iterator of (nodes after it in the ordering, nodes before it, the node), each list sorted, built one node at a time
+fn seed_neighborhoods(graph: &HashMap<u32, HashSet<u32>>, min_common: usize) -> impl Iterator<Item = (Vec<u32>, Vec<u32>, u32)> + '_ {
+    let mut order: Vec<u32> = graph.keys().cloned().collect();
+    order.sort_by_key(|node| (graph[node].len(), *node)); // Hubs come last, so they search few later nodes
+    let position: HashMap<u32, usize> = order.iter().enumerate().map(|(idx, node)| (*node, idx)).collect();
+
+    order.into_iter().map(move |seed| {
+        let mut common: HashMap<u32, usize> = graph[&seed].iter().map(|neighbor| (*neighbor, 0)).collect();
+        for neighbor in &graph[&seed] {
+            for two_hop in &graph[neighbor] {
+                *common.entry(*two_hop).or_insert(0) += 1;
+            }
+        }
+        let (mut later, mut earlier): (Vec<u32>, Vec<u32>) = common.into_iter()
+            .filter(|(node, count)| *node != seed && *count >= min_common)
+            .map(|(node, _)| node)
+            .partition(|node| position[node] > position[&seed]);
+        later.sort();
+        earlier.sort();
+        (later, earlier, seed)
+    })
+}
+
+/// True when adding node to the k-plex members keeps it a k-plex, both for node and for the members it isn't connected to
+fn can_join_plex(graph: &HashMap<u32, HashSet<u32>>, members: &[u32], node: &u32, k: usize) -> bool {
+    let missing: Vec<&u32> = members.iter().filter(|member| !graph[node].contains(member)).collect();
+    missing.len() < k && missing.iter().all(|member| members.len() + 1 - inner_degree(graph, member, members) <= k)
+}
+
+/// One Bron-Kerbosch step over k-plexes, see maximal_k_plexes \
+/// candidates can each join members, and excluded holds nodes that could join but were already searched
+fn expand_plex(
+    graph: &HashMap<u32, HashSet<u32>>,
+    k: usize,
+    min_size: usize,
+    members: &mut Vec<u32>,
+    mut candidates: Vec<u32>,
+    mut excluded: Vec<u32>,
+    plexes: &mut Vec<Vec<u32>>,
+) {
+    if members.len() + candidates.len() < min_size {
+        return
+    }
+    // A member of a large enough k-plex needs min_size - k neighbors among the members and candidates,
+    // so candidates without them are dropped until none are left, and the branch ends if a member lacks them
+    loop {
+        let pool: HashSet<u32> = members.iter().chain(&candidates).cloned().collect();
+        let kept: Vec<u32> = candidates.iter().filter(|node| pool_degree(graph, node, &pool) + k >= min_size).cloned().collect();
+        if kept.len() == candidates.len() {
+            if members.iter().any(|member| pool_degree(graph, member, &pool) + k < min_size) {
+                return
+            }
+            break
+        }
+        candidates = kept;
+    }
+
+    if candidates.is_empty() {
+        if excluded.is_empty() && members.len() >= min_size {
+            let mut plex = members.clone();
+            plex.sort();
+            plexes.push(plex);
+        }
+        return
+    }
+
+    for (idx, node) in candidates.iter().enumerate() {
+        if members.len() + candidates.len() - idx < min_size {
+            return // Even taking every remaining candidate is too small
+        }
+        members.push(*node);
+        let next_candidates = candidates[idx + 1..].iter().filter(|other| can_join_plex(graph, members, other, k)).cloned().collect();
+        let next_excluded = excluded.iter().filter(|other| can_join_plex(graph, members, other, k)).cloned().collect();
+        expand_plex(graph, k, min_size, members, next_candidates, next_excluded, plexes);
+        members.pop();
+        excluded.push(*node);
+    }
+}
+
+/// True when some node outside the quasi-clique members can join it and keep it a quasi-clique
+fn extendable_quasi(graph: &HashMap<u32, HashSet<u32>>, gamma: f64, members: &[u32]) -> bool {
+    let needed = quasi_degree(gamma, members.len() + 1);
+    let inner: Vec<usize> = members.iter().map(|member| inner_degree(graph, member, members)).collect();
+    let nearby: HashSet<&u32> = members.iter().flat_map(|member| &graph[member]).filter(|node| !members.contains(node)).collect();
+    nearby.into_iter().any(|node| {
+        inner_degree(graph, node, members) >= needed
+            && members.iter().zip(&inner).all(|(member, degree)| degree + graph[node].contains(member) as usize >= needed)
+    })
+}
+
+/// One step of the quasi-clique search, see maximal_quasi_cliques \
+/// Keeps members if it's a maximal quasi-clique, then tries adding each candidate in turn, with only the candidates after it left for deeper steps
+fn expand_quasi(
+    graph: &HashMap<u32, HashSet<u32>>,
+    gamma: f64,
+    min_size: usize,
+    members: &mut Vec<u32>,
+    mut candidates: Vec<u32>,
+    found: &mut Vec<Vec<u32>>,
+) {
+    // Members a node isn't connected to stay missing as the group grows, so each member's missing count
+    // sets a smallest final size, and candidates missing too many members can't join
+    let missing_bound = |missing: usize| if missing == 0 { 0 } else if gamma >= 1.0 { usize::MAX } else {
+        (missing as f64 / (1.0 - gamma) - 1e-9).ceil() as usize + 1
+    };
+    let smallest = members.iter()
+        .map(|member| missing_bound(members.len() - 1 - inner_degree(graph, member, members)))
+        .max()
+        .unwrap_or(0)
+        .max(min_size);
+    // A node in a large enough quasi-clique needs enough neighbors among the members and candidates,
+    // so candidates without them are dropped until none are left
+    let needed = quasi_degree(gamma, smallest);
+    let pool = loop {
+        let pool: HashSet<u32> = members.iter().chain(&candidates).cloned().collect();
+        let kept: Vec<u32> = candidates.iter().filter(|node| pool_degree(graph, node, &pool) >= needed).cloned().collect();
+        if kept.len() == candidates.len() {
+            break pool
+        }
+        candidates = kept;
+    };
+    // Each member's neighbors in the pool also cap the size of any quasi-clique it can be in
+    let largest = members.iter()
+        .map(|member| (pool_degree(graph, member, &pool) as f64 / gamma + 1e-9).floor() as usize + 1)
+        .min()
+        .unwrap_or(0)
+        .min(pool.len());
+    if largest < smallest {
+        return
+    }
+    candidates.retain(|node| missing_bound(members.len() - inner_degree(graph, node, members)) <= largest);
+
+    if members.len() >= min_size && is_quasi_clique(graph, members, gamma) && !extendable_quasi(graph, gamma, members) {
+        let mut quasi_clique = members.clone();
+        quasi_clique.sort();
+        found.push(quasi_clique);
+    }
+    for (idx, node) in candidates.iter().enumerate() {
+        if members.len() + candidates.len() - idx < min_size {
+            return // Even taking every remaining candidate is too small
+        }
+        members.push(*node);
+        expand_quasi(graph, gamma, min_size, members, candidates[idx + 1..].to_vec(), found);
+        members.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+use super::*;
+    use crate::{bron_kerbosch, random_graphs};
+
+    /// Finds every maximal group of at least min_size nodes passing is_group by checking every subset of nodes
+    fn brute_force(graph: &HashMap<u32, HashSet<u32>>, min_size: usize, is_group: impl Fn(&[u32]) -> bool) -> Vec<Vec<u32>> {
+        let mut nodes: Vec<u32> = graph.keys().cloned().collect();
+        nodes.sort();
+        let members = |mask: u32| -> Vec<u32> { (0..nodes.len()).filter(|a| mask & (1 << a) != 0).map(|a| nodes[a]).collect() };
+        let groups: Vec<u32> = (1u32..(1 << nodes.len()))
+            .filter(|mask| mask.count_ones() as usize >= min_size && is_group(&members(*mask)))
+            .collect();
+        let mut maximal: Vec<Vec<u32>> = groups.iter()
+            .filter(|mask| !groups.iter().any(|other| other != *mask && other & *mask == **mask))
+            .map(|mask| members(*mask))
+            .collect();
+        maximal.sort();
+        maximal
+    }
+
+    /// A clique missing one edge is a single 2-plex, 1-plexes are cliques, and random graphs match brute force
+    #[test]
+    fn test_k_plexes() {
+        let edges: Vec<(u32, u32)> = (1..=5).flat_map(|a| (a + 1..=5).map(move |b| (a, b))).filter(|edge| *edge != (1, 2)).collect();
+        let graph = random_graphs::graph_from_edges(&edges);
+        assert_eq!(maximal_k_plexes(&graph, 2, 3), vec![vec![1, 2, 3, 4, 5]]);
+        assert_eq!(maximal_k_plexes(&graph, 1, 3), vec![vec![1, 3, 4, 5], vec![2, 3, 4, 5]]);
+
+        for seed in 0..5 {
+            let graph = random_graphs::erdos_renyi(14, 0.4, seed);
+            assert_eq!(maximal_k_plexes(&graph, 1, 3), bron_kerbosch::maximal_cliques(&graph, 3), "Seed {}", seed);
+            assert_eq!(maximal_k_plexes(&graph, 2, 4), brute_force(&graph, 4, |group| is_k_plex(&graph, group, 2)), "Seed {}", seed);
+        }
+    }
+
+    /// Quasi-cliques at gamma 1.0 are cliques, and random graphs match brute force at lower gamma
+    #[test]
+    fn test_quasi_cliques() {
+        for seed in 0..5 {
+            let graph = random_graphs::erdos_renyi(14, 0.4, seed);
+            assert_eq!(maximal_quasi_cliques(&graph, 1.0, 3), bron_kerbosch::maximal_cliques(&graph, 3), "Seed {}", seed);
+            for gamma in [0.5, 0.7] {
+                assert_eq!(maximal_quasi_cliques(&graph, gamma, 4), brute_force(&graph, 4, |group| is_quasi_clique(&graph, group, gamma)),
+                    "Seed {}, gamma {}", seed, gamma);
+            }
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! Module for cross-region transfer learning, training a node classifier on one region and evaluating it on another
+use std::collections::{HashMap, HashSet};
+use crate::data_analysis::NodeStats;
+use crate::file_reading;
+use crate::graph_cache;
+use crate::node_classifier::{self, ClassificationMetrics, LogisticRegression, SparseFeatures, FEATURE_COUNT};
+
+/// Graph, features and labels of a single region
+#[derive(Debug, Clone)]
+pub struct RegionData {
+    pub name: String,
+    pub graph: HashMap<u32, HashSet<u32>>,
+    pub features: HashMap<u32, Vec<u32>>,
+    pub labels: HashMap<u32, bool>,
+}
+
+/// Loads a region's edges, target and features files, reading the edges and target through graph_cache::load_region_cached \
+/// cache_dir is the folder of graph cache files, such as graph_cache \
+/// label picks the NodeStats field being predicted, such as |node| node.mature or |node| node.partner
+pub fn load_region(
+    name: &str,
+    edge_path: &str,
+    target_path: &str,
+    features_path: &str,
+    cache_dir: &str,
+    label: fn(&NodeStats) -> bool
+) -> Result<RegionData, Box<dyn std::error::Error>> {
+    let region = graph_cache::load_region_cached(edge_path, target_path, cache_dir)?;
+    let stats = region.stats;
+    Ok(RegionData {
+        name: name.to_string(),
+        graph: region.graph,
+        features: file_reading::load_features(features_path)?,
+        labels: stats.values().map(|node| (node.new_id, label(node))).collect(),
+    })
+}
+
+/// Trains on every node of the source region and evaluates on every node of the target region \
+/// ### Logic
+/// Without graph features, a logistic regression on the node features is trained on the source and scored on the target \
+/// With graph features, the target's labels are unknown, so the neighbor label average can't use them.
+/// Instead the feature-only model first predicts the target's labels, and those predictions
+/// stand in for known labels when building the target's graph features \
+/// ### Output
+/// ClassificationMetrics of the predictions on the target region
+pub fn transfer_metrics(source: &RegionData, target: &RegionData, use_graph: bool, seed: u64) -> ClassificationMetrics {
+    let mut source_ids: Vec<u32> = source.labels.keys().cloned().collect();
+    source_ids.sort();
+    let target_ids: Vec<u32> = target.labels.keys().cloned().collect();
+
+    let feature_model = train_on_region(source, &source_ids, false, seed);
+    let target_vectors = node_classifier::build_feature_vectors(&target_ids, &target.features, None, &HashMap::new());
+    let mut predictions: Vec<(f64, bool)> = target_ids.iter()
+        .map(|node| (feature_model.predict_proba(&target_vectors[node]), target.labels[node]))
+        .collect();
+
+    if use_graph {
+        let pseudo_labels: HashMap<u32, bool> = target_ids.iter().zip(&predictions)
+            .map(|(node, (probability, _))| (*node, *probability >= 0.5)) // Feature-only predictions replace the hidden labels
+            .collect();
+        let graph_model = train_on_region(source, &source_ids, true, seed);
+        let graph_vectors = node_classifier::build_feature_vectors(&target_ids, &target.features, Some(&target.graph), &pseudo_labels);
+        predictions = target_ids.iter()
+            .map(|node| (graph_model.predict_proba(&graph_vectors[node]), target.labels[node]))
+            .collect();
+    }
+    node_classifier::classification_metrics(&predictions)
+}
+
+/// Trains a logistic regression on the given nodes of a region, with the same settings as evaluate_classifier
+fn train_on_region(region: &RegionData, node_ids: &[u32], use_graph: bool, seed: u64) -> LogisticRegression {
+    let graph = if use_graph { Some(&region.graph) } else { None };
+    let vectors = node_classifier::build_feature_vectors(node_ids, &region.features, graph, &region.labels);
+    let samples: Vec<(&SparseFeatures, bool)> = node_ids.iter().map(|node| (&vectors[node], region.labels[node])).collect();
+    LogisticRegression::train(&samples, FEATURE_COUNT + 2, 20, 0.05, 0.0001, seed)
+}
+
+/// Builds the full transfer matrix, where row i column j trains on region i and evaluates on region j \
+/// The diagonal can't evaluate on its own training nodes, so it uses evaluate_classifier's 80/20 holdout split instead \
+/// **Output:** square vector of vectors of ClassificationMetrics, in the order the regions were given
+pub fn transfer_matrix(regions: &[RegionData], use_graph: bool, seed: u64) -> Vec<Vec<ClassificationMetrics>> {
+    let mut matrix = Vec::new();
+    for source in regions {
+        let mut row = Vec::new();
+        for target in regions {
+            if source.name == target.name {
+                let graph = if use_graph { Some(&source.graph) } else { None };
+                row.push(node_classifier::evaluate_classifier(&source.features, &source.labels, graph, 0.2, seed));
+            } else {
+                row.push(transfer_metrics(source, target, use_graph, seed));
+            }
+        }
+        matrix.push(row);
+    }
+    matrix
+}
+
+#[cfg(test)]
+mod tests {
+use super::*;
+
+    /// Builds a region where feature 7 marks positive nodes and friends mostly share a label
+    fn synthetic_region(name: &str, offset: u32) -> RegionData {
+        let mut region = RegionData { name: name.to_string(), graph: HashMap::new(), features: HashMap::new(), labels: HashMap::new() };
+        for node in 0..100u32 {
+            let label = (node + offset).is_multiple_of(3);
+            region.features.insert(node, if label { vec![7, node % 4 + 20] } else { vec![node % 4 + 20] });
+            region.labels.insert(node, label);
+            let friend = (node + 3) % 100; // Labels repeat every 3 ids, so friends share a label apart from where the ids wrap around
+            region.graph.entry(node).or_default().insert(friend);
+            region.graph.entry(friend).or_default().insert(node);
+        }
+        region
+    }
+
+    /// A shared feature decides the label in both regions, so the model should transfer
+    #[test]
+    fn test_transfer() {
+        let regions = vec![synthetic_region("A", 0), synthetic_region("B", 1)];
+
+        let metrics = transfer_metrics(&regions[0], &regions[1], false, 5);
+        assert_eq!(metrics.accuracy, 1.0);
+
+        let matrix = transfer_matrix(&regions, true, 5);
+        assert_eq!(matrix.len(), 2);
+        assert_eq!(matrix[0].len(), 2);
+        assert!(matrix[0][1].roc_auc > 0.9, "Graph features should not break the transfer");
+    }
+}
@@ -0,0 +1,142 @@
+//! Module for finding the cliques with the most combined weight, such as total viewership, rather than the most members
+use std::collections::{HashMap, HashSet};
+use crate::bron_kerbosch;
+use crate::data_analysis::NodeStats;
+
+/// Builds vertex weights from NodeStats with any function, such as |node| node.views as f64 \
+/// **Output:** HashMap of new_ids to weights, which should not be negative
+pub fn node_weights(stats: &HashMap<u32, NodeStats>, weight: impl Fn(&NodeStats) -> f64) -> HashMap<u32, f64> {
+    stats.iter().map(|(node, node_stats)| (*node, weight(node_stats))).collect()
+}
+
+/// Total weight of a clique, counting nodes without a weight as 0
+pub fn clique_weight(clique: &[u32], weights: &HashMap<u32, f64>) -> f64 {
+    clique.iter().map(|node| weights.get(node).cloned().unwrap_or(0.0)).sum()
+}
+
+/// Finds the clique with the largest total weight by branch and bound \
+/// ### Inputs
+/// graph: HashMap graph such as the output of csv_to_hashmap \
+/// weights: HashMap of node ids to non-negative weights, such as the output of node_weights, missing nodes weigh 0 \
+/// ### Algorithm Logic
+/// Grow the clique one node at a time, only ever adding nodes connected to every current member \
+/// Before each choice, color the candidates greedily so no two neighbors share a color \
+/// A clique takes at most one node per color, so the current weight plus the heaviest node of each color
+/// bounds every clique that can still be built, as does the current weight plus every candidate's weight \
+/// Branches where either bound can't beat the best clique found are skipped \
+/// ### Output
+/// Sorted heaviest clique and its weight, or an empty clique with weight 0 for an empty graph \
+/// The clique is maximal whenever every weight is above 0
+pub fn maximum_weight_clique(graph: &HashMap<u32, HashSet<u32>>, weights: &HashMap<u32, f64>) -> (Vec<u32>, f64) {
+    let weight_of = |node: &u32| weights.get(node).cloned().unwrap_or(0.0);
+    let mut candidates: Vec<u32> = graph.keys().cloned().collect();
+    candidates.sort_by(|a, b| weight_of(b).total_cmp(&weight_of(a)).then(a.cmp(b))); // Heaviest first, ids break ties
+    let mut best = (Vec::new(), f64::NEG_INFINITY);
+    expand(graph, &weight_of, &mut Vec::new(), 0.0, candidates, &mut best);
+
+    let (mut clique, weight) = best;
+    clique.sort();
+    (clique, weight.max(0.0))
+}
+
+/// One branch and bound step, see maximum_weight_clique \
+/// candidates are the nodes connected to every member of current, heaviest first
+fn expand(
+    graph: &HashMap<u32, HashSet<u32>>,
+    weight_of: &impl Fn(&u32) -> f64,
+    current: &mut Vec<u32>,
+    current_weight: f64,
+    candidates: Vec<u32>,
+    best: &mut (Vec<u32>, f64),
+) {
+    if candidates.is_empty() {
+        if current_weight > best.1 {
+            *best = (current.clone(), current_weight);
+        }
+        return
+    }
+
+    // Greedy coloring, heaviest nodes first, so the first node of each color is its heaviest
+    let mut colors: Vec<Vec<u32>> = Vec::new();
+    for node in &candidates {
+        match colors.iter_mut().find(|color| color.iter().all(|member| !graph[node].contains(member))) {
+            Some(color) => color.push(*node),
+            None => colors.push(vec![*node]),
+        }
+    }
+    // Ordered by color, each node's bound is the heaviest weight of its color and every earlier color
+    let mut ordered: Vec<(u32, f64)> = Vec::new();
+    let mut bound = 0.0;
+    for color in &colors {
+        bound += weight_of(&color[0]);
+        ordered.extend(color.iter().map(|node| (*node, bound)));
+    }
+
+    while let Some((node, node_bound)) = ordered.pop() {
+        if current_weight + node_bound <= best.1 {
+            return // Every remaining node has a bound at most this one
+        }
+        let mut next: Vec<u32> = ordered.iter()
+            .map(|(other, _)| *other)
+            .filter(|other| graph[&node].contains(other))
+            .collect();
+        let next_weight = current_weight + weight_of(&node);
+        if next_weight + next.iter().map(weight_of).sum::<f64>() <= best.1 {
+            continue // Even taking every remaining neighbor can't beat the best clique, which is cheaper to check than coloring
+        }
+        next.sort_by(|a, b| weight_of(b).total_cmp(&weight_of(a)).then(a.cmp(b)));
+        current.push(node);
+        expand(graph, weight_of, current, next_weight, next, best);
+        current.pop();
+    }
+}
+
+/// Finds the k heaviest maximal cliques, by running Bron-Kerbosch for every maximal clique of at least min_value nodes \
+/// **Output:** up to k (clique, weight) pairs, heaviest first, with ties broken by the cliques' sorted members
+pub fn top_weighted_cliques(graph: &HashMap<u32, HashSet<u32>>, weights: &HashMap<u32, f64>, k: usize, min_value: u32) -> Vec<(Vec<u32>, f64)> {
+    let mut weighted: Vec<(Vec<u32>, f64)> = bron_kerbosch::maximal_cliques(graph, min_value).into_iter()
+        .map(|clique| {
+            let weight = clique_weight(&clique, weights);
+            (clique, weight)
+        })
+        .collect();
+    weighted.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    weighted.truncate(k);
+    weighted
+}
+
+#[cfg(test)]
+mod tests {
+use super::*;
+    use crate::random_graphs;
+    use rand::{Rng, SeedableRng};
+    use rand::rngs::StdRng;
+
+    /// The heaviest clique can be smaller than the largest one
+    #[test]
+    fn test_maximum_weight_clique() {
+        let graph = random_graphs::graph_from_edges(&[(1, 2), (1, 3), (1, 4), (2, 3), (2, 4), (3, 4), (4, 5), (5, 6), (4, 6)]);
+        let mut stats = HashMap::new();
+        for (new_id, views) in [(1, 10), (2, 10), (3, 10), (4, 5), (5, 100), (6, 1)] {
+            stats.insert(new_id, NodeStats { id: new_id, new_id, views, days: 1, mature: false, partner: false });
+        }
+        let weights = node_weights(&stats, |node| node.views as f64);
+
+        assert_eq!(maximum_weight_clique(&graph, &weights), (vec![4, 5, 6], 106.0));
+        let top = top_weighted_cliques(&graph, &weights, 5, 1);
+        assert_eq!(top, vec![(vec![4, 5, 6], 106.0), (vec![1, 2, 3, 4], 35.0)]);
+    }
+
+    /// Branch and bound should agree with the heaviest maximal clique from Bron-Kerbosch on random graphs
+    #[test]
+    fn test_against_enumeration() {
+        for seed in 0..10 {
+            let graph = random_graphs::erdos_renyi(40, 0.3, seed);
+            let mut rng = StdRng::seed_from_u64(seed);
+            let weights: HashMap<u32, f64> = (0..40).map(|node| (node, rng.random_range(1..1000) as f64)).collect();
+            let (clique, weight) = maximum_weight_clique(&graph, &weights);
+            assert_eq!(weight, clique_weight(&clique, &weights));
+            assert_eq!(weight, top_weighted_cliques(&graph, &weights, 1, 1)[0].1, "Seed {}", seed);
+        }
+    }
+}